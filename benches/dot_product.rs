@@ -0,0 +1,52 @@
+use rustnet::common::{
+    dense_matrix::Matrix,
+    gemm::{blocked_dot_product, dot_product_strassen, STRASSEN_THRESHOLD},
+    matrix::dot_product,
+};
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 3;
+
+// (rows, shared, cols): forward pass, weight gradient, and square hidden layer products
+const SHAPES: [(usize, usize, usize); 4] = [
+    (10, 784, 10000),
+    (10, 10000, 784),
+    (512, 512, 512),
+    (1024, 1024, 1024),
+];
+
+fn best_of<F: FnMut()>(mut f: F) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    for (m, k, n) in SHAPES {
+        let matrix_1 = Matrix::from_fn(m, k, |i, j| ((i * 7 + j * 3) % 11) as f32 - 5.0);
+        let matrix_2 = Matrix::from_fn(k, n, |i, j| ((i * 5 + j * 13) % 17) as f32 / 17.0);
+
+        let (nested_1, nested_2) = (matrix_1.to_nested(), matrix_2.to_nested());
+
+        let naive = best_of(|| {
+            dot_product(&nested_1, &nested_2).unwrap();
+        });
+        let blocked = best_of(|| {
+            blocked_dot_product(&matrix_1, &matrix_2);
+        });
+        let strassen = best_of(|| {
+            dot_product_strassen(&matrix_1, &matrix_2, STRASSEN_THRESHOLD);
+        });
+
+        println!(
+            "{m}x{k} * {k}x{n}: dot_product {naive:?}, blocked_dot_product {blocked:?} ({:.1}x), dot_product_strassen {strassen:?} ({:.1}x)",
+            naive.as_secs_f64() / blocked.as_secs_f64(),
+            naive.as_secs_f64() / strassen.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,536 @@
+use crate::common::activation::{default_activations, Activation};
+use crate::common::canvas::init_canvas;
+use crate::common::config::{
+    early_stopping_from_name, optimizer_from_name, per_layer, schedule_from_name, TrainingConfig,
+};
+use crate::common::constants::PREDICTORS_FOLDER;
+use crate::common::dataset::{read_dataset, read_dataset_file};
+use crate::common::dense_matrix::Matrix;
+use crate::common::error::{Error, Result};
+use crate::common::evaluation::evaluate;
+use crate::common::idx::{is_idx_file, read_idx_images, read_mnist};
+use crate::common::initializer::{default_initializers, Initializer};
+use crate::common::io::{
+    check_results_exist, load_checkpoint, load_model, load_network_params, migrate_csv_model,
+    read_csv_file, read_file_into_vector, remove_checkpoint, save_checkpoint, save_model,
+    MODEL_FILE,
+};
+use crate::common::loss::Loss;
+use crate::common::matrix::create_rng;
+use crate::common::model_file::ModelMetadata;
+use crate::common::network_functions::{
+    check_dataset, continue_training, forward_propagation, get_predictions, prepare_data,
+    split_dataset, TrainingState,
+};
+use crate::common::regularization::Regularization;
+use crate::common::types::NetworkParams;
+use clap::{Args, Parser, Subcommand};
+use rand_pcg::Pcg64;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Train a handwritten digit classifier and use it from the terminal.
+/// Flags override the values read from `.env`.
+#[derive(Debug, Parser)]
+#[command(name = "rustnet", version)]
+pub struct Cli {
+    /// Directory the predictors are saved to and loaded from
+    #[arg(short, long, global = true, env = "MODEL_DIR", default_value = PREDICTORS_FOLDER)]
+    pub model_dir: PathBuf,
+    /// Without a command the saved network is drawn with, or trained when there is none
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Train a new network and save its predictors
+    Train(Box<TrainArgs>),
+    /// Report accuracy, a confusion matrix and per-class metrics of the saved network
+    Eval(InputArgs),
+    /// Print the predicted digit of every image in a CSV file
+    Predict(PredictArgs),
+    /// Draw a digit in the terminal and let the saved network guess it
+    Draw,
+    /// Print the layers of the saved network and how it was trained
+    Inspect,
+    /// Convert predictors saved as CSV files to the binary model file
+    Migrate,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct InputArgs {
+    /// Headerless CSV file with one sample per row or an IDX image file (optionally gzipped),
+    /// reads a CSV from stdin when omitted
+    #[arg(short, long)]
+    pub input: Option<String>,
+    /// IDX label file (optionally gzipped) of the IDX images passed with --input
+    #[arg(short, long)]
+    pub labels: Option<String>,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct TrainArgs {
+    /// Labelled dataset, the label is the first value of every row
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Retrain even if saved predictors exist
+    #[arg(short, long)]
+    pub force: bool,
+    #[arg(long)]
+    pub iterations: Option<usize>,
+    #[arg(long)]
+    pub alpha: Option<f32>,
+    /// Learning rate schedule: constant, step, exponential, cosine or plateau
+    #[arg(long)]
+    pub schedule: Option<String>,
+    /// Iterations ramping the learning rate up linearly to --alpha
+    #[arg(long)]
+    pub warmup: Option<usize>,
+    /// Comma separated layer widths, e.g. 784,128,10
+    #[arg(long, value_delimiter = ',')]
+    pub layers: Option<Vec<usize>>,
+    /// Comma separated activations for every layer after the input, e.g. relu,softmax
+    #[arg(long, value_delimiter = ',')]
+    pub activations: Option<Vec<Activation>>,
+    /// Comma separated weight initializers for every layer after the input, e.g. he_normal,xavier_uniform
+    #[arg(long, value_delimiter = ',')]
+    pub initializers: Option<Vec<Initializer>>,
+    /// L1 penalty, one value for every layer after the input or a single value for all of them
+    #[arg(long, value_delimiter = ',')]
+    pub l1: Option<Vec<f32>>,
+    /// Coupled L2 penalty (weight decay added to the gradients), per layer like --l1
+    #[arg(long, value_delimiter = ',')]
+    pub l2: Option<Vec<f32>>,
+    /// Decoupled AdamW style weight decay, per layer like --l1
+    #[arg(long, value_delimiter = ',')]
+    pub weight_decay: Option<Vec<f32>>,
+    /// Maximum L2 norm of the incoming weights of every unit, per layer like --l1, 0 for none
+    #[arg(long, value_delimiter = ',')]
+    pub max_norm: Option<Vec<f32>>,
+    /// Apply the L1, L2 and weight decay to the biases as well
+    #[arg(long)]
+    pub regularize_biases: bool,
+    /// Dropout rate of the hidden layers, one value for every hidden layer or a single value for
+    /// all of them
+    #[arg(long, value_delimiter = ',')]
+    pub dropout: Option<Vec<f32>>,
+    /// Samples per mini-batch, 0 trains on the whole dataset every iteration
+    #[arg(long)]
+    pub batch_size: Option<usize>,
+    /// sgd, momentum, nesterov, rmsprop, adagrad or adam
+    #[arg(long)]
+    pub optimizer: Option<String>,
+    /// cross_entropy, mse, binary_cross_entropy or hinge
+    #[arg(long)]
+    pub loss: Option<Loss>,
+    /// Share of the dataset held out to evaluate the network
+    #[arg(long)]
+    pub validation_split: Option<f32>,
+    /// Stop once the validation loss or accuracy stops improving and keep the best network
+    #[arg(long)]
+    pub early_stopping: Option<String>,
+    /// Iterations without improvement before stopping early
+    #[arg(long)]
+    pub patience: Option<usize>,
+    /// Smallest change of the monitored metric counting as an improvement
+    #[arg(long)]
+    pub min_delta: Option<f32>,
+    /// Seed for weight initialisation and shuffling, runs with the same seed train the same network
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Save a checkpoint to the model directory every N iterations, 0 saves none
+    #[arg(long)]
+    pub checkpoint_every: Option<usize>,
+    /// Continue the run of the latest checkpoint instead of starting a new one
+    #[arg(long)]
+    pub resume: bool,
+}
+
+impl TrainArgs {
+    /// Overrides the values of `config` with the flags that were passed.
+    pub fn apply(&self, config: &mut TrainingConfig) -> Result<()> {
+        if let Some(iterations) = self.iterations {
+            config.iterations = iterations;
+        }
+        if let Some(alpha) = self.alpha {
+            config.alpha = alpha;
+        }
+        if let Some(schedule) = &self.schedule {
+            config.schedule = schedule_from_name(schedule)?;
+        }
+        if let Some(warmup) = self.warmup {
+            config.warmup = warmup;
+        }
+        if let Some(layers) = &self.layers {
+            // activations and initializers configured for a different topology don't fit anymore
+            if layers.len() != config.layer_sizes.len() {
+                config.activations = default_activations(layers.len().saturating_sub(1));
+                config.initializers = default_initializers(layers.len().saturating_sub(1));
+                config.regularization =
+                    resize_per_layer(&config.regularization, layers.len().saturating_sub(1));
+                config.dropout = resize_per_layer(&config.dropout, layers.len().saturating_sub(2));
+            }
+            config.layer_sizes = layers.clone();
+        }
+        if let Some(activations) = &self.activations {
+            config.activations = activations.clone();
+        }
+        if let Some(initializers) = &self.initializers {
+            config.initializers = initializers.clone();
+        }
+        let layer_count = config.activations.len();
+        for (name, values, set) in [
+            ("--l1", &self.l1, set_l1 as fn(&mut Regularization, f32)),
+            ("--l2", &self.l2, set_l2),
+            ("--weight-decay", &self.weight_decay, set_weight_decay),
+            ("--max-norm", &self.max_norm, set_max_norm),
+        ] {
+            if let Some(values) = values {
+                let values = per_layer(name, values, layer_count)?;
+                if config.regularization.len() != layer_count {
+                    config.regularization = vec![Regularization::default(); layer_count];
+                }
+                for (reg, value) in config.regularization.iter_mut().zip(values) {
+                    set(reg, value);
+                }
+            }
+        }
+        if self.regularize_biases {
+            config.regularize_biases = true;
+        }
+        if let Some(dropout) = &self.dropout {
+            config.dropout = per_layer(
+                "--dropout",
+                dropout,
+                config.layer_sizes.len().saturating_sub(2),
+            )?;
+        }
+        if let Some(batch_size) = self.batch_size {
+            config.batch_size = Some(batch_size).filter(|size| *size > 0);
+        }
+        if let Some(optimizer) = &self.optimizer {
+            config.optimizer = optimizer_from_name(optimizer)?;
+        }
+        if let Some(loss) = self.loss {
+            config.loss = loss;
+        }
+        if let Some(validation_split) = self.validation_split {
+            config.validation_split = validation_split;
+        }
+        if let Some(monitor) = &self.early_stopping {
+            config.early_stopping = Some(early_stopping_from_name(monitor)?);
+        }
+        if self.patience.is_some() || self.min_delta.is_some() {
+            let Some(early_stopping) = &mut config.early_stopping else {
+                return Err(Error::InvalidConfig(
+                    "--patience and --min-delta need --early-stopping".to_owned(),
+                ));
+            };
+            if let Some(patience) = self.patience {
+                early_stopping.patience = patience;
+            }
+            if let Some(min_delta) = self.min_delta {
+                early_stopping.min_delta = min_delta;
+            }
+        }
+        if let Some(seed) = self.seed {
+            config.seed = Some(seed);
+        }
+        if let Some(checkpoint_every) = self.checkpoint_every {
+            config.checkpoint_every = Some(checkpoint_every).filter(|every| *every > 0);
+        }
+
+        config.validate()
+    }
+}
+
+fn set_l1(reg: &mut Regularization, value: f32) {
+    reg.l1 = value;
+}
+
+fn set_l2(reg: &mut Regularization, value: f32) {
+    reg.l2 = value;
+}
+
+fn set_weight_decay(reg: &mut Regularization, value: f32) {
+    reg.weight_decay = value;
+}
+
+fn set_max_norm(reg: &mut Regularization, value: f32) {
+    // 0 leaves the layer unconstrained, negative values are kept for validate to reject
+    reg.max_norm = Some(value).filter(|max_norm| *max_norm != 0.0);
+}
+
+/// Per layer setting for a different number of layers, kept when every layer had the same value.
+fn resize_per_layer<T: Copy + Default + PartialEq>(values: &[T], layer_count: usize) -> Vec<T> {
+    match values.first() {
+        Some(first) if values.iter().all(|value| value == first) => vec![*first; layer_count],
+        _ => vec![T::default(); layer_count],
+    }
+}
+
+#[derive(Debug, Default, Args)]
+pub struct PredictArgs {
+    /// Images with one pixel (0-255) per value, rows may start with a label like the training data
+    #[command(flatten)]
+    pub input: InputArgs,
+    /// Write one prediction per line to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+pub fn run(cli: Cli) -> Result<()> {
+    let model_dir = cli.model_dir.as_path();
+
+    match cli.command {
+        Some(Command::Train(args)) => train_command(&args, model_dir),
+        Some(Command::Eval(args)) => eval_command(&args, model_dir),
+        Some(Command::Predict(args)) => predict_command(&args, model_dir),
+        Some(Command::Draw) => init_canvas(model_dir),
+        Some(Command::Inspect) => inspect_command(model_dir),
+        Some(Command::Migrate) => {
+            migrate_csv_model(model_dir)?;
+            println!("Saved {}", model_dir.join(MODEL_FILE).display());
+            Ok(())
+        }
+        None => match check_results_exist(model_dir) {
+            true => init_canvas(model_dir),
+            false => train_command(&TrainArgs::default(), model_dir),
+        },
+    }
+}
+
+fn train_command(args: &TrainArgs, model_dir: &Path) -> Result<()> {
+    if check_results_exist(model_dir) && !args.force && !args.resume {
+        println!(
+            "Predictors found in {}, use `train --force` to retrain",
+            model_dir.display()
+        );
+        return Ok(());
+    }
+
+    let mut config = TrainingConfig::from_env()?;
+    args.apply(&mut config)?;
+
+    let checkpoint = match args.resume {
+        true => Some(load_checkpoint(model_dir)?),
+        false => None,
+    };
+
+    // the dataset is shuffled and split with the seed of the run, a resumed run needs the same
+    let seed = match &checkpoint {
+        Some(checkpoint) => checkpoint.seed,
+        None => config.seed.unwrap_or_else(rand::random),
+    };
+    let mut rng = create_rng(Some(seed));
+    let (labels, data) = load_dataset(&args.input, &mut rng)?;
+    let layer_sizes = &config.layer_sizes;
+    check_dataset(
+        &labels,
+        &data,
+        (layer_sizes[1], layer_sizes[0]),
+        *layer_sizes.last().unwrap(),
+    )?;
+
+    let ((train_labels, train_data), test_set) =
+        split_dataset(labels, data, config.validation_split)?;
+
+    let mut state = match checkpoint {
+        Some(checkpoint) => {
+            println!("Resuming training at iteration {}", checkpoint.iteration);
+            TrainingState::resume(checkpoint, &config)?
+        }
+        None => {
+            println!("Training a new network");
+            TrainingState::new(&config, &mut rng)
+        }
+    };
+
+    continue_training(
+        &mut state,
+        &train_labels,
+        &train_data,
+        test_set.as_ref().map(|(labels, data)| (labels, data)),
+        &config,
+        |state| match config.checkpoint_every {
+            Some(every) if state.iteration % every == 0 => {
+                save_checkpoint(&state.checkpoint(seed), model_dir)
+            }
+            _ => Ok(()),
+        },
+    )?;
+    // early stopping hands back the network of its best iteration
+    let iterations = match config.early_stopping {
+        Some(_) if !state.early_stopping.best_params.is_empty() => {
+            state.early_stopping.best_iteration
+        }
+        _ => state.iteration,
+    };
+    let network_params = state.network_params;
+
+    let accuracy = test_set.map(|(test_labels, test_data)| {
+        let evaluation = evaluate(&network_params, &test_labels, &test_data);
+        print!("{evaluation}");
+        evaluation.accuracy()
+    });
+
+    let metadata = ModelMetadata {
+        iterations,
+        alpha: config.alpha,
+        batch_size: config.batch_size.unwrap_or(0),
+        loss: config.loss.to_string(),
+        accuracy,
+    };
+
+    save_model(&network_params, &metadata, model_dir)?;
+    remove_checkpoint(model_dir)?;
+
+    println!("Predictors generated, run `draw` to launch the prediction canvas");
+
+    Ok(())
+}
+
+fn eval_command(args: &InputArgs, model_dir: &Path) -> Result<()> {
+    let network_params = load_network_params(model_dir)?;
+    let (labels, data) = load_dataset(args, &mut create_rng(None))?;
+    check_dataset(
+        &labels,
+        &data,
+        network_params[0].weights.shape(),
+        network_params.last().unwrap().weights.rows(),
+    )?;
+
+    print!("{}", evaluate(&network_params, &labels, &data));
+
+    Ok(())
+}
+
+fn predict_command(args: &PredictArgs, model_dir: &Path) -> Result<()> {
+    let network_params = load_network_params(model_dir)?;
+    let input_size = network_params[0].weights.cols();
+
+    let images = read_input(&args.input)?
+        .into_iter()
+        .enumerate()
+        .map(|(row, pixels)| match pixels.len() {
+            len if len == input_size => Ok(pixels),
+            len if len == input_size + 1 => Ok(pixels[1..].to_vec()),
+            len => Err(Error::InvalidInput(format!(
+                "expected {input_size} pixels per image, got {len} in row {row}"
+            ))),
+        })
+        .collect::<Result<Vec<Vec<f32>>>>()?;
+
+    if images.is_empty() {
+        return Err(Error::InvalidInput(
+            "there are no images to predict".to_owned(),
+        ));
+    }
+
+    // images: m x inputs, the network takes one sample per column
+    let data = Matrix::from(images).transpose().divide(255.0);
+    let forward_cache = forward_propagation(&network_params, &data);
+    let predictions = get_predictions(&forward_cache.last().unwrap().activation);
+
+    let lines: String = predictions
+        .iter()
+        .map(|prediction| format!("{prediction}\n"))
+        .collect();
+
+    match &args.output {
+        Some(path) => fs::write(path, lines)?,
+        None => print!("{lines}"),
+    }
+
+    Ok(())
+}
+
+fn inspect_command(model_dir: &Path) -> Result<()> {
+    let network_params = if model_dir.join(MODEL_FILE).is_file() {
+        let (network_params, metadata) = load_model(model_dir)?;
+        print!("{}", describe_metadata(&metadata));
+        network_params
+    } else {
+        let network_params = load_network_params(model_dir)?;
+        println!("CSV predictors without metadata, run `migrate` to convert them");
+        network_params
+    };
+
+    print!("{}", describe_network(&network_params));
+
+    Ok(())
+}
+
+/// Training settings of a saved model, unknown values are left out.
+pub fn describe_metadata(metadata: &ModelMetadata) -> String {
+    let mut description = String::new();
+
+    if metadata.iterations > 0 {
+        description += &format!("Iterations: {}\n", metadata.iterations);
+    }
+    if metadata.alpha > 0.0 {
+        description += &format!("Alpha: {}\n", metadata.alpha);
+    }
+    if metadata.batch_size > 0 {
+        description += &format!("Batch size: {}\n", metadata.batch_size);
+    }
+    if !metadata.loss.is_empty() {
+        description += &format!("Loss: {}\n", metadata.loss);
+    }
+    if let Some(accuracy) = metadata.accuracy {
+        description += &format!("Test accuracy: {accuracy}\n");
+    }
+
+    description
+}
+
+/// One line per layer with its shape, activation and parameter count.
+pub fn describe_network(network_params: &NetworkParams) -> String {
+    let mut description = String::new();
+    let mut total = 0;
+
+    for (i, layer) in network_params.iter().enumerate() {
+        let parameters = layer.weights.as_slice().len() + layer.biases.as_slice().len();
+        total += parameters;
+
+        description += &format!(
+            "Layer {}: {} -> {} ({}), {parameters} parameters\n",
+            i + 1,
+            layer.weights.cols(),
+            layer.weights.rows(),
+            layer.activation
+        );
+    }
+
+    description + &format!("Total parameters: {total}\n")
+}
+
+fn read_input(args: &InputArgs) -> Result<Vec<Vec<f32>>> {
+    match (&args.input, &args.labels) {
+        (Some(images), Some(labels)) => read_mnist(images, labels),
+        (None, Some(_)) => Err(Error::InvalidInput(
+            "--labels needs the IDX images passed with --input".to_owned(),
+        )),
+        (Some(path), None) if is_idx_file(path)? => read_idx_images(path),
+        (Some(path), None) => read_csv_file(path),
+        (None, None) => read_file_into_vector(),
+    }
+}
+
+/// Shuffled labels (1xm) and normalised samples (inputs x m) of a labelled dataset,
+/// CSV datasets are streamed straight into the matrices.
+fn load_dataset(args: &InputArgs, rng: &mut Pcg64) -> Result<(Matrix, Matrix)> {
+    match (&args.input, &args.labels) {
+        (Some(images), Some(labels)) => Ok(prepare_data(read_mnist(images, labels)?, rng)),
+        (None, Some(_)) => Err(Error::InvalidInput(
+            "--labels needs the IDX images passed with --input".to_owned(),
+        )),
+        (Some(path), None) if is_idx_file(path)? => Err(Error::InvalidInput(format!(
+            "{path} holds IDX images without labels, pass them with --labels"
+        ))),
+        (Some(path), None) => read_dataset_file(path, rng),
+        (None, None) => read_dataset(io::stdin().lock(), 0, rng),
+    }
+}
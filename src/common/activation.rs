@@ -0,0 +1,195 @@
+#![allow(dead_code)]
+use super::dense_matrix::Matrix;
+use super::network_functions::{relu, softmax};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+// sqrt(2 / pi) used by the tanh approximation of GELU
+const GELU_COEFF: f32 = 0.797_884_6;
+const GELU_CUBIC: f32 = 0.044_715;
+
+/// Activation function applied to the output of a dense layer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    Relu,
+    /// Slope used for negative inputs.
+    LeakyRelu(f32),
+    /// Saturation value (alpha) for negative inputs.
+    Elu(f32),
+    Gelu,
+    Sigmoid,
+    Tanh,
+    Softplus,
+    Identity,
+    /// Column wise softmax, usually the output layer of a classifier.
+    Softmax,
+}
+
+impl Activation {
+    pub fn forward(&self, z: &Matrix) -> Matrix {
+        match self {
+            Activation::Relu => relu(z),
+            Activation::Softmax => softmax(z),
+            _ => z.map(|x| self.apply(x)),
+        }
+    }
+
+    /// Element-wise derivative with respect to `z`.
+    /// Softmax has no element-wise derivative, use `backward` instead.
+    pub fn derivative(&self, z: &Matrix) -> Matrix {
+        match self {
+            Activation::Softmax => panic!("Softmax has no element-wise derivative, use backward"),
+            _ => z.map(|x| self.apply_derivative(x)),
+        }
+    }
+
+    /// Gradient with respect to `z` given the gradient with respect to the `activation`.
+    pub fn backward(&self, z: &Matrix, activation: &Matrix, gradient: &Matrix) -> Matrix {
+        match self {
+            Activation::Softmax => {
+                // dz = a * (da - sum(a * da)) per column
+                let weighted = activation.multiply(gradient).col_sum();
+
+                Matrix::from_fn(activation.rows(), activation.cols(), |i, j| {
+                    activation[(i, j)] * (gradient[(i, j)] - weighted[j])
+                })
+            }
+            _ => gradient.multiply(&self.derivative(z)),
+        }
+    }
+
+    fn apply(&self, x: f32) -> f32 {
+        match *self {
+            Activation::Relu => x.max(0.0),
+            Activation::LeakyRelu(slope) => {
+                if x > 0.0 {
+                    x
+                } else {
+                    slope * x
+                }
+            }
+            Activation::Elu(alpha) => {
+                if x > 0.0 {
+                    x
+                } else {
+                    alpha * x.exp_m1()
+                }
+            }
+            Activation::Gelu => {
+                0.5 * x * (1.0 + (GELU_COEFF * (x + GELU_CUBIC * x * x * x)).tanh())
+            }
+            Activation::Sigmoid => sigmoid(x),
+            Activation::Tanh => x.tanh(),
+            // log(1 + e^x) without overflowing for large x
+            Activation::Softplus => x.max(0.0) + (-x.abs()).exp().ln_1p(),
+            Activation::Identity => x,
+            Activation::Softmax => unreachable!("softmax is not element-wise"),
+        }
+    }
+
+    fn apply_derivative(&self, x: f32) -> f32 {
+        match *self {
+            Activation::Relu => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::LeakyRelu(slope) => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    slope
+                }
+            }
+            Activation::Elu(alpha) => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    alpha * x.exp()
+                }
+            }
+            Activation::Gelu => {
+                let inner = GELU_COEFF * (x + GELU_CUBIC * x * x * x);
+                let tanh = inner.tanh();
+                let inner_derivative = GELU_COEFF * (1.0 + 3.0 * GELU_CUBIC * x * x);
+
+                0.5 * (1.0 + tanh) + 0.5 * x * (1.0 - tanh * tanh) * inner_derivative
+            }
+            Activation::Sigmoid => {
+                let s = sigmoid(x);
+                s * (1.0 - s)
+            }
+            Activation::Tanh => 1.0 - x.tanh().powi(2),
+            Activation::Softplus => sigmoid(x),
+            Activation::Identity => 1.0,
+            Activation::Softmax => unreachable!("softmax is not element-wise"),
+        }
+    }
+}
+
+pub fn sigmoid(x: f32) -> f32 {
+    // avoid exp overflow for large negative inputs
+    if x >= 0.0 {
+        1.0 / (1.0 + (-x).exp())
+    } else {
+        let e = x.exp();
+        e / (1.0 + e)
+    }
+}
+
+impl Display for Activation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Activation::Relu => write!(f, "relu"),
+            Activation::LeakyRelu(slope) => write!(f, "leaky_relu:{slope}"),
+            Activation::Elu(alpha) => write!(f, "elu:{alpha}"),
+            Activation::Gelu => write!(f, "gelu"),
+            Activation::Sigmoid => write!(f, "sigmoid"),
+            Activation::Tanh => write!(f, "tanh"),
+            Activation::Softplus => write!(f, "softplus"),
+            Activation::Identity => write!(f, "identity"),
+            Activation::Softmax => write!(f, "softmax"),
+        }
+    }
+}
+
+/// Parses names like `relu`, `tanh` or `leaky_relu:0.1`, the optional value after
+/// the colon is the slope of LeakyReLU or the alpha of ELU.
+impl FromStr for Activation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.trim().to_lowercase();
+        let (name, param) = match lowercase.split_once(':') {
+            Some((name, param)) => {
+                let param = param
+                    .parse::<f32>()
+                    .map_err(|_| format!("Invalid activation parameter: {s}"))?;
+                (name.to_owned(), Some(param))
+            }
+            None => (lowercase, None),
+        };
+
+        match name.as_str() {
+            "relu" => Ok(Activation::Relu),
+            "leaky_relu" => Ok(Activation::LeakyRelu(param.unwrap_or(0.01))),
+            "elu" => Ok(Activation::Elu(param.unwrap_or(1.0))),
+            "gelu" => Ok(Activation::Gelu),
+            "sigmoid" => Ok(Activation::Sigmoid),
+            "tanh" => Ok(Activation::Tanh),
+            "softplus" => Ok(Activation::Softplus),
+            "identity" | "linear" => Ok(Activation::Identity),
+            "softmax" => Ok(Activation::Softmax),
+            _ => Err(format!("Unknown activation: {s}")),
+        }
+    }
+}
+
+/// Default activations for a network: relu for every hidden layer and softmax for the output.
+pub fn default_activations(layer_count: usize) -> Vec<Activation> {
+    let mut activations = vec![Activation::Relu; layer_count.saturating_sub(1)];
+    activations.push(Activation::Softmax);
+    activations
+}
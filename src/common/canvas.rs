@@ -1,9 +1,13 @@
 #![allow(dead_code)]
 use crate::common::console::draw;
 
+use super::error::Result;
+use super::io::load_network_params;
 use super::network_functions::predict;
+use super::types::NetworkParams;
 use console_engine::{pixel, Color, ConsoleEngine, KeyCode, MouseButton};
 use std::ops::ControlFlow;
+use std::path::Path;
 
 pub fn draw_canvas_bounds(
     engine: &mut console_engine::ConsoleEngine,
@@ -56,13 +60,14 @@ pub fn setup_canvas_controls(
     top_instructions: &mut String,
     max_px: u32,
     top_msg: &str,
+    network_params: &NetworkParams,
 ) -> ControlFlow<()> {
     if engine.is_key_pressed(KeyCode::Char('q')) {
         // if the user presses 'q' :
         return ControlFlow::Break(()); // exits app
     }
 
-    predict_hook(engine, user_input, top_instructions);
+    predict_hook(engine, user_input, top_instructions, network_params);
 
     if engine.is_key_pressed(KeyCode::Char('e')) {
         // erase the console
@@ -76,6 +81,7 @@ pub fn predict_hook(
     engine: &console_engine::ConsoleEngine,
     user_input: &[Vec<f32>],
     top_instructions: &mut String,
+    network_params: &NetworkParams,
 ) {
     if engine.is_key_pressed(KeyCode::Char('p')) {
         // remove the first row and column of the canvas as it is 1 px larger than our input
@@ -84,6 +90,7 @@ pub fn predict_hook(
                 .iter()
                 .map(|row| row[1..].to_vec())
                 .collect(),
+            network_params,
         );
         *top_instructions = format!("Prediction:{prediction}");
     }
@@ -106,12 +113,14 @@ pub fn setup_mouse_actions(
     }
 }
 
-pub fn init_canvas() {
+pub fn init_canvas(model_dir: &Path) -> Result<()> {
+    let network_params = load_network_params(model_dir)?;
+
     println!("Predictors found, please use the terminal to draw a number");
 
     let max_px = 29;
 
-    let mut engine = console_engine::ConsoleEngine::init_fill_require(max_px, max_px, 30).unwrap();
+    let mut engine = console_engine::ConsoleEngine::init_fill_require(max_px, max_px, 30)?;
 
     // main loop, be aware that you'll have to break it because ctrl+C is captured
     let mut user_input: Vec<Vec<f32>> =
@@ -132,6 +141,7 @@ pub fn init_canvas() {
             &mut top_instructions,
             max_px,
             &top_msg,
+            &network_params,
         ) {
             break;
         }
@@ -145,4 +155,6 @@ pub fn init_canvas() {
 
         engine.draw(); // draw the screen
     }
+
+    Ok(())
 }
@@ -0,0 +1,431 @@
+#![allow(dead_code)]
+use super::activation::{default_activations, Activation};
+use super::early_stopping::{EarlyStopping, Monitor};
+use super::error::{Error, Result};
+use super::initializer::{default_initializers, Initializer};
+use super::loss::Loss;
+use super::optimizer::OptimizerKind;
+use super::regularization::Regularization;
+use super::schedule::Schedule;
+use std::env;
+
+/// Hyperparameters used by `train`, read from the environment (see `.env_sample`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingConfig {
+    /// Layer widths starting with the input size, e.g. `[784, 128, 64, 10]`.
+    pub layer_sizes: Vec<usize>,
+    /// One activation for every layer after the input.
+    pub activations: Vec<Activation>,
+    /// One weight initializer for every layer after the input.
+    pub initializers: Vec<Initializer>,
+    /// One regularisation for every layer after the input.
+    pub regularization: Vec<Regularization>,
+    /// Applies the L1, L2 and decoupled weight decay to the biases as well as the weights.
+    pub regularize_biases: bool,
+    /// Dropout rate of every hidden layer, 0 keeps every unit.
+    pub dropout: Vec<f32>,
+    /// Full-batch iterations, or epochs when training with mini-batches.
+    pub iterations: usize,
+    /// Base learning rate, `schedule` decides how it changes over the run.
+    pub alpha: f32,
+    pub schedule: Schedule,
+    /// Iterations ramping the learning rate up linearly before the schedule starts.
+    pub warmup: usize,
+    /// Samples per mini-batch, `None` trains on the whole dataset every iteration.
+    pub batch_size: Option<usize>,
+    pub optimizer: OptimizerKind,
+    pub loss: Loss,
+    /// Share of the samples held out of training and used to evaluate the network.
+    pub validation_split: f32,
+    /// Stops when the validation metric stops improving and keeps the best network,
+    /// `None` trains for all iterations.
+    pub early_stopping: Option<EarlyStopping>,
+    /// Seeds weight initialisation and shuffling, `None` gives a different network every run.
+    pub seed: Option<u64>,
+    /// Iterations between checkpoints of the model directory, `None` writes none.
+    pub checkpoint_every: Option<usize>,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        TrainingConfig {
+            layer_sizes: vec![784, 10, 10],
+            activations: default_activations(2),
+            initializers: default_initializers(2),
+            regularization: vec![Regularization::default(); 2],
+            regularize_biases: false,
+            dropout: vec![0.0],
+            iterations: 200,
+            alpha: 0.15,
+            schedule: Schedule::Constant,
+            warmup: 0,
+            batch_size: None,
+            optimizer: OptimizerKind::Sgd,
+            loss: Loss::CrossEntropy,
+            validation_split: 0.0,
+            early_stopping: None,
+            seed: None,
+            checkpoint_every: None,
+        }
+    }
+}
+
+impl TrainingConfig {
+    pub fn from_env() -> Result<Self> {
+        let defaults = TrainingConfig::default();
+
+        let iterations = env_or("ITERATIONS", defaults.iterations)?;
+        let alpha = env_or("ALPHA", defaults.alpha)?;
+
+        let layer_sizes = match env::var("LAYERS") {
+            Ok(layers) => parse_layer_sizes(&layers)?,
+            Err(_) => defaults.layer_sizes,
+        };
+
+        let activations = match env::var("ACTIVATIONS") {
+            Ok(activations) => parse_activations(&activations)?,
+            Err(_) => default_activations(layer_sizes.len().saturating_sub(1)),
+        };
+
+        let initializers = match env::var("INITIALIZERS") {
+            Ok(initializers) => parse_initializers(&initializers)?,
+            Err(_) => default_initializers(layer_sizes.len().saturating_sub(1)),
+        };
+
+        let regularization = regularization_from_env(layer_sizes.len().saturating_sub(1))?;
+
+        let hidden_layer_count = layer_sizes.len().saturating_sub(2);
+        let dropout = match env::var("DROPOUT") {
+            Ok(rates) => per_layer(
+                "DROPOUT",
+                &parse_values("DROPOUT", &rates)?,
+                hidden_layer_count,
+            )?,
+            Err(_) => vec![0.0; hidden_layer_count],
+        };
+
+        // a batch size of 0 or an unset variable means full-batch training
+        let batch_size = Some(env_or("BATCH_SIZE", 0)?).filter(|size| *size > 0);
+
+        let config = TrainingConfig {
+            layer_sizes,
+            activations,
+            initializers,
+            regularization,
+            regularize_biases: env_or("REGULARIZE_BIASES", defaults.regularize_biases)?,
+            dropout,
+            iterations,
+            alpha,
+            schedule: schedule_from_env()?,
+            warmup: env_or("WARMUP", defaults.warmup)?,
+            batch_size,
+            optimizer: optimizer_from_env()?,
+            loss: match env::var("LOSS") {
+                Ok(loss) => loss.parse::<Loss>().map_err(Error::InvalidConfig)?,
+                Err(_) => defaults.loss,
+            },
+            validation_split: env_or("VALIDATION_SPLIT", defaults.validation_split)?,
+            early_stopping: match env::var("EARLY_STOPPING") {
+                Ok(monitor) => Some(early_stopping_from_name(&monitor)?),
+                Err(_) => None,
+            },
+            seed: env_opt("SEED")?,
+            checkpoint_every: Some(env_or("CHECKPOINT_EVERY", 0)?).filter(|every| *every > 0),
+        };
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks the settings `train` would otherwise panic on.
+    pub fn validate(&self) -> Result<()> {
+        if self.layer_sizes.len() < 2 {
+            return Err(Error::InvalidConfig(
+                "a network needs at least an input and an output layer".to_owned(),
+            ));
+        }
+        if self.layer_sizes.contains(&0) {
+            return Err(Error::InvalidConfig(
+                "every layer needs at least one unit".to_owned(),
+            ));
+        }
+        if self.activations.len() != self.layer_sizes.len() - 1 {
+            return Err(Error::InvalidConfig(format!(
+                "{} layers after the input need as many activations, got {}",
+                self.layer_sizes.len() - 1,
+                self.activations.len()
+            )));
+        }
+        if self.initializers.len() != self.activations.len() {
+            return Err(Error::InvalidConfig(format!(
+                "{} layers after the input need as many initializers, got {}",
+                self.activations.len(),
+                self.initializers.len()
+            )));
+        }
+        if self.regularization.len() != self.activations.len() {
+            return Err(Error::InvalidConfig(format!(
+                "{} layers after the input need as many regularisations, got {}",
+                self.activations.len(),
+                self.regularization.len()
+            )));
+        }
+        for reg in &self.regularization {
+            let valid = [reg.l1, reg.l2, reg.weight_decay]
+                .iter()
+                .all(|value| *value >= 0.0)
+                && !matches!(reg.max_norm, Some(max_norm) if max_norm <= 0.0 || max_norm.is_nan());
+
+            if !valid {
+                return Err(Error::InvalidConfig(format!(
+                    "regularisation coefficients can't be negative, got {reg:?}"
+                )));
+            }
+        }
+        if self.dropout.len() != self.layer_sizes.len() - 2 {
+            return Err(Error::InvalidConfig(format!(
+                "{} hidden layers need as many dropout rates, got {}",
+                self.layer_sizes.len() - 2,
+                self.dropout.len()
+            )));
+        }
+        if let Some(rate) = self.dropout.iter().find(|rate| !(0.0..1.0).contains(*rate)) {
+            return Err(Error::InvalidConfig(format!(
+                "dropout rates must be between 0 and 1, got {rate}"
+            )));
+        }
+        if !(self.alpha.is_finite() && self.alpha > 0.0) {
+            return Err(Error::InvalidConfig(format!(
+                "the learning rate must be positive, got {}",
+                self.alpha
+            )));
+        }
+        match self.schedule {
+            Schedule::Step { step_size: 0, .. }
+            | Schedule::Cosine { period: 0, .. }
+            | Schedule::Cosine { period_mult: 0, .. } => {
+                return Err(Error::InvalidConfig(
+                    "learning rate schedule periods need at least one iteration".to_owned(),
+                ))
+            }
+            Schedule::Plateau { factor, .. } if !(factor > 0.0 && factor < 1.0) => {
+                return Err(Error::InvalidConfig(format!(
+                    "the plateau factor must be between 0 and 1, got {factor}"
+                )))
+            }
+            Schedule::Step { gamma, .. } | Schedule::Exponential { gamma }
+                if !(gamma > 0.0 && gamma <= 1.0) =>
+            {
+                return Err(Error::InvalidConfig(format!(
+                    "the decay factor gamma must be above 0 and at most 1, got {gamma}"
+                )))
+            }
+            Schedule::Cosine { min_alpha, .. } | Schedule::Plateau { min_alpha, .. }
+                if !(0.0..=self.alpha).contains(&min_alpha) =>
+            {
+                return Err(Error::InvalidConfig(format!(
+                    "the minimum learning rate must be between 0 and alpha {}, got {min_alpha}",
+                    self.alpha
+                )))
+            }
+            _ => {}
+        }
+        let (fractions, epsilon) = match self.optimizer {
+            OptimizerKind::Sgd => (vec![], None),
+            OptimizerKind::Momentum { momentum, .. } => (vec![("momentum", momentum)], None),
+            OptimizerKind::RmsProp { decay, epsilon } => (vec![("decay", decay)], Some(epsilon)),
+            OptimizerKind::Adagrad { epsilon } => (vec![], Some(epsilon)),
+            OptimizerKind::Adam {
+                beta_1,
+                beta_2,
+                epsilon,
+            } => (vec![("beta_1", beta_1), ("beta_2", beta_2)], Some(epsilon)),
+        };
+        if let Some((name, value)) = fractions
+            .into_iter()
+            .find(|(_, value)| !(0.0..1.0).contains(value))
+        {
+            return Err(Error::InvalidConfig(format!(
+                "the optimizer {name} must be at least 0 and below 1, got {value}"
+            )));
+        }
+        if let Some(epsilon) = epsilon.filter(|epsilon| *epsilon <= 0.0 || epsilon.is_nan()) {
+            return Err(Error::InvalidConfig(format!(
+                "the optimizer epsilon must be positive, got {epsilon}"
+            )));
+        }
+        if let Some(early_stopping) = &self.early_stopping {
+            if early_stopping.min_delta < 0.0 || early_stopping.min_delta.is_nan() {
+                return Err(Error::InvalidConfig(format!(
+                    "the early stopping minimum delta can't be negative, got {}",
+                    early_stopping.min_delta
+                )));
+            }
+        }
+        if !(0.0..1.0).contains(&self.validation_split) {
+            return Err(Error::InvalidConfig(format!(
+                "the validation split must be between 0 and 1, got {}",
+                self.validation_split
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads an optional numeric setting, falling back to `default` when it is not set.
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> Result<T> {
+    Ok(env_opt(name)?.unwrap_or(default))
+}
+
+fn env_opt<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| Error::InvalidConfig(format!("{name} has an invalid value: {value}"))),
+        Err(_) => Ok(None),
+    }
+}
+
+fn optimizer_from_env() -> Result<OptimizerKind> {
+    optimizer_from_name(&env::var("OPTIMIZER").unwrap_or_else(|_| "sgd".to_owned()))
+}
+
+/// Optimizer by name, its hyperparameters are still read from the environment.
+pub fn optimizer_from_name(name: &str) -> Result<OptimizerKind> {
+    let epsilon = env_or("EPSILON", 1e-8)?;
+
+    Ok(match name.trim().to_lowercase().as_str() {
+        "sgd" => OptimizerKind::Sgd,
+        "momentum" | "nesterov" => OptimizerKind::Momentum {
+            momentum: env_or("MOMENTUM", 0.9)?,
+            nesterov: name.trim().eq_ignore_ascii_case("nesterov"),
+        },
+        "rmsprop" => OptimizerKind::RmsProp {
+            decay: env_or("RMS_DECAY", 0.9)?,
+            epsilon,
+        },
+        "adagrad" => OptimizerKind::Adagrad { epsilon },
+        "adam" => OptimizerKind::Adam {
+            beta_1: env_or("BETA_1", 0.9)?,
+            beta_2: env_or("BETA_2", 0.999)?,
+            epsilon,
+        },
+        other => return Err(Error::InvalidConfig(format!("Unknown OPTIMIZER: {other}"))),
+    })
+}
+
+fn schedule_from_env() -> Result<Schedule> {
+    schedule_from_name(&env::var("LR_SCHEDULE").unwrap_or_else(|_| "constant".to_owned()))
+}
+
+/// Learning rate schedule by name, its hyperparameters are still read from the environment.
+pub fn schedule_from_name(name: &str) -> Result<Schedule> {
+    let min_alpha = env_or("MIN_ALPHA", 0.0)?;
+
+    Ok(match name.trim().to_lowercase().as_str() {
+        "constant" => Schedule::Constant,
+        "step" => Schedule::Step {
+            step_size: env_or("LR_STEP_SIZE", 30)?,
+            gamma: env_or("LR_GAMMA", 0.1)?,
+        },
+        "exponential" => Schedule::Exponential {
+            gamma: env_or("LR_GAMMA", 0.95)?,
+        },
+        "cosine" => Schedule::Cosine {
+            period: env_or("LR_PERIOD", 50)?,
+            period_mult: env_or("LR_PERIOD_MULT", 1)?,
+            min_alpha,
+        },
+        "plateau" => Schedule::Plateau {
+            factor: env_or("LR_FACTOR", 0.5)?,
+            patience: env_or("LR_PATIENCE", 10)?,
+            min_alpha,
+        },
+        other => {
+            return Err(Error::InvalidConfig(format!(
+                "Unknown LR_SCHEDULE: {other}"
+            )))
+        }
+    })
+}
+
+/// Early stopping monitoring `loss` or `accuracy`, its patience and minimum delta are still
+/// read from the environment.
+pub fn early_stopping_from_name(monitor: &str) -> Result<EarlyStopping> {
+    Ok(EarlyStopping {
+        monitor: monitor.parse::<Monitor>().map_err(Error::InvalidConfig)?,
+        patience: env_or("PATIENCE", 10)?,
+        min_delta: env_or("MIN_DELTA", 0.0)?,
+    })
+}
+
+fn regularization_from_env(layer_count: usize) -> Result<Vec<Regularization>> {
+    let values = |name: &str| match env::var(name) {
+        Ok(values) => per_layer(name, &parse_values(name, &values)?, layer_count),
+        Err(_) => Ok(vec![0.0; layer_count]),
+    };
+
+    let (l1, l2) = (values("L1")?, values("L2")?);
+    let (weight_decay, max_norm) = (values("WEIGHT_DECAY")?, values("MAX_NORM")?);
+
+    Ok((0..layer_count)
+        .map(|l| Regularization {
+            l1: l1[l],
+            l2: l2[l],
+            weight_decay: weight_decay[l],
+            max_norm: Some(max_norm[l]).filter(|max_norm| *max_norm != 0.0),
+        })
+        .collect())
+}
+
+fn parse_values(name: &str, values: &str) -> Result<Vec<f32>> {
+    values
+        .split(',')
+        .map(|value| {
+            value
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| Error::InvalidConfig(format!("{name} has an invalid value: {value}")))
+        })
+        .collect()
+}
+
+/// Values of a per layer setting for `layer_count` layers, a single value applies to all.
+pub fn per_layer(name: &str, values: &[f32], layer_count: usize) -> Result<Vec<f32>> {
+    match values.len() {
+        1 => Ok(vec![values[0]; layer_count]),
+        len if len == layer_count => Ok(values.to_vec()),
+        len => Err(Error::InvalidConfig(format!(
+            "{name} needs 1 or {layer_count} values, got {len}"
+        ))),
+    }
+}
+
+pub fn parse_layer_sizes(layers: &str) -> Result<Vec<usize>> {
+    layers
+        .split(',')
+        .map(|size| {
+            size.trim()
+                .parse::<usize>()
+                .map_err(|_| Error::InvalidConfig(format!("Invalid layer size: {size}")))
+        })
+        .collect()
+}
+
+pub fn parse_activations(activations: &str) -> Result<Vec<Activation>> {
+    activations
+        .split(',')
+        .map(|name| name.parse::<Activation>().map_err(Error::InvalidConfig))
+        .collect()
+}
+
+pub fn parse_initializers(initializers: &str) -> Result<Vec<Initializer>> {
+    initializers
+        .split(',')
+        .map(|name| name.parse::<Initializer>().map_err(Error::InvalidConfig))
+        .collect()
+}
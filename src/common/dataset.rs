@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+//! Loads labelled CSV datasets straight into the layout the network trains on,
+//! without the intermediate `Vec<Vec<f32>>` copies of `read_file_into_vector` and `prepare_data`.
+use super::dense_matrix::Matrix;
+use super::error::{Error, Result};
+use super::matrix::fisher_yates;
+use csv::{ReaderBuilder, StringRecord};
+use rand::Rng;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Reads a labelled CSV file, the buffer is sized from the file length up front.
+pub fn read_dataset_file<P: AsRef<Path>, R: Rng>(path: P, rng: &mut R) -> Result<(Matrix, Matrix)> {
+    let file = File::open(path)?;
+    // every value takes at least one digit and a separator, so this is an upper bound
+    let capacity = file.metadata()?.len() as usize / 2;
+
+    read_dataset(file, capacity, rng)
+}
+
+/// Streams a CSV dataset with the label first on every row, like the one `prepare_data` takes,
+/// and returns the shuffled labels (1xm) and the pixels divided by 255 (inputs x m).
+/// A first row without a single number is a header and skipped.
+/// `capacity` is the number of values to reserve room for, 0 lets the buffer grow as needed.
+pub fn read_dataset<T: Read, R: Rng>(
+    reader: T,
+    capacity: usize,
+    rng: &mut R,
+) -> Result<(Matrix, Matrix)> {
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(reader);
+    let mut record = StringRecord::new();
+
+    let mut labels = Vec::new();
+    let mut pixels: Vec<f32> = Vec::with_capacity(capacity);
+
+    let mut row = 0;
+
+    while reader.read_record(&mut record)? {
+        let start = pixels.len();
+        match parse_sample(&record, row, &mut pixels) {
+            Ok(label) => labels.push(label),
+            // a header names the columns instead of holding numbers
+            Err(_) if row == 0 && is_header(&record) => pixels.truncate(start),
+            Err(e) => return Err(e),
+        }
+        row += 1;
+    }
+
+    let sample_count = labels.len();
+    let inputs = pixels.len().checked_div(sample_count).unwrap_or(0);
+    // the unused tail of the reservation is never written, shrinking the buffer would copy
+    // every pixel into a second one instead
+
+    // samples x inputs, one sample per row like the file
+    let mut data = Matrix::new(sample_count, inputs, pixels);
+    shuffle_samples(&mut labels, &mut data, rng);
+    data.transpose_in_place();
+
+    Ok((Matrix::new(1, sample_count, labels), data))
+}
+
+/// A row none of whose fields are numbers, a row mixing both is a broken sample instead.
+fn is_header(record: &StringRecord) -> bool {
+    record
+        .iter()
+        .all(|field| field.trim().parse::<f32>().is_err())
+}
+
+/// Appends the pixels of a row to `pixels` and returns its label.
+fn parse_sample(record: &StringRecord, row: usize, pixels: &mut Vec<f32>) -> Result<f32> {
+    let parse = |column: usize, field: &str| {
+        field.trim().parse::<f32>().map_err(|_| Error::Parse {
+            row,
+            column,
+            value: field.to_owned(),
+        })
+    };
+
+    let label = parse(0, &record[0])?;
+    for (column, field) in record.iter().enumerate().skip(1) {
+        pixels.push(parse(column, field)? / 255.0);
+    }
+
+    Ok(label)
+}
+
+/// Shuffles the rows of `data` and `labels` together.
+fn shuffle_samples<R: Rng>(labels: &mut [f32], data: &mut Matrix, rng: &mut R) {
+    fisher_yates(labels.len(), rng, |i, j| {
+        labels.swap(i, j);
+        data.swap_rows(i, j);
+    });
+}
@@ -0,0 +1,437 @@
+#![allow(dead_code)]
+use super::error::{Error, Result};
+use super::gemm::blocked_dot_product;
+use super::simd;
+use std::ops::{Index, IndexMut, Range};
+
+/// Dense row-major matrix backed by a single contiguous buffer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f32>,
+}
+
+/// Borrowed rectangular block of a `Matrix`.
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixView<'a> {
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    data: &'a [f32],
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, data: Vec<f32>) -> Self {
+        if rows * cols != data.len() {
+            panic!(
+                "A {rows}x{cols} matrix needs {} values, got {}!",
+                rows * cols,
+                data.len()
+            );
+        }
+
+        Matrix { rows, cols, data }
+    }
+
+    pub fn zeroes(rows: usize, cols: usize) -> Self {
+        Matrix::filled(rows, cols, 0.0)
+    }
+
+    pub fn filled(rows: usize, cols: usize, value: f32) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![value; rows * cols],
+        }
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> f32>(rows: usize, cols: usize, mut f: F) -> Self {
+        let mut data = Vec::with_capacity(rows * cols);
+
+        for i in 0..rows {
+            for j in 0..cols {
+                data.push(f(i, j));
+            }
+        }
+
+        Matrix { rows, cols, data }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// (rows, cols)
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    pub fn into_vec(self) -> Vec<f32> {
+        self.data
+    }
+
+    pub fn row(&self, i: usize) -> &[f32] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [f32] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[f32]> {
+        // chunks_exact(0) panics, a matrix without columns has no values to yield anyway
+        self.data.chunks_exact(self.cols.max(1)).take(self.rows)
+    }
+
+    /// Copies column `j`, columns are not contiguous in a row-major buffer.
+    pub fn column(&self, j: usize) -> Vec<f32> {
+        self.iter_rows().map(|row| row[j]).collect()
+    }
+
+    pub fn view(&self, rows: Range<usize>, cols: Range<usize>) -> MatrixView<'_> {
+        if rows.end > self.rows || cols.end > self.cols {
+            panic!(
+                "View {rows:?}x{cols:?} is out of bounds of a {}x{} matrix!",
+                self.rows, self.cols
+            );
+        }
+
+        let start = rows.start * self.cols + cols.start;
+        let end = if rows.is_empty() {
+            start
+        } else {
+            (rows.end - 1) * self.cols + cols.end
+        };
+
+        MatrixView {
+            rows: rows.len(),
+            cols: cols.len(),
+            stride: self.cols,
+            data: &self.data[start..end],
+        }
+    }
+
+    /// View of the whole matrix.
+    pub fn as_view(&self) -> MatrixView<'_> {
+        self.view(0..self.rows, 0..self.cols)
+    }
+
+    /// Builds a matrix from the given columns, in the order of `indices`.
+    pub fn select_columns(&self, indices: &[usize]) -> Matrix {
+        let mut data = Vec::with_capacity(self.rows * indices.len());
+
+        for row in self.iter_rows() {
+            data.extend(indices.iter().map(|&j| row[j]));
+        }
+
+        Matrix::new(self.rows, indices.len(), data)
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut data = vec![0.0; self.data.len()];
+
+        for (i, row) in self.iter_rows().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                data[j * self.rows + i] = *cell;
+            }
+        }
+
+        Matrix::new(self.cols, self.rows, data)
+    }
+
+    /// Transposes without a second buffer by following the permutation cycles,
+    /// slower than `transpose` but only needs one bit per value to track them.
+    pub fn transpose_in_place(&mut self) {
+        let len = self.data.len();
+
+        if self.rows > 1 && self.cols > 1 {
+            // the value at i = r * cols + c moves to c * rows + r = i * rows mod (len - 1),
+            // the first and the last value stay where they are
+            let last = len - 1;
+            let mut visited = vec![0u64; len / 64 + 1];
+
+            for start in 1..last {
+                if visited[start / 64] & (1 << (start % 64)) != 0 {
+                    continue;
+                }
+
+                let mut value = self.data[start];
+                let mut i = start;
+                loop {
+                    i = (i as u128 * self.rows as u128 % last as u128) as usize;
+                    std::mem::swap(&mut self.data[i], &mut value);
+                    visited[i / 64] |= 1 << (i % 64);
+
+                    if i == start {
+                        break;
+                    }
+                }
+            }
+        }
+
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+
+    pub fn swap_rows(&mut self, i: usize, j: usize) {
+        if i == j {
+            return;
+        }
+
+        let (first, second) = (i.min(j), i.max(j));
+        let (head, tail) = self.data.split_at_mut(second * self.cols);
+        head[first * self.cols..(first + 1) * self.cols].swap_with_slice(&mut tail[..self.cols]);
+    }
+
+    /// Matrix product, see `gemm::blocked_dot_product`.
+    /// Panics when the shapes don't fit, use `try_dot` for untrusted shapes.
+    pub fn dot(&self, other: &Matrix) -> Matrix {
+        blocked_dot_product(self, other)
+    }
+
+    pub fn try_dot(&self, other: &Matrix) -> Result<Matrix> {
+        if self.cols != other.rows {
+            return Err(Error::ShapeMismatch {
+                left: self.shape(),
+                right: other.shape(),
+            });
+        }
+
+        Ok(self.dot(other))
+    }
+
+    pub fn map<F: Fn(f32) -> f32>(&self, f: F) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&x| f(x)).collect(),
+        }
+    }
+
+    pub fn map_in_place<F: Fn(f32) -> f32>(&mut self, f: F) {
+        self.data.iter_mut().for_each(|x| *x = f(*x));
+    }
+
+    /// Applies `f` to the values at the same position of both matrices.
+    pub fn zip_map<F: Fn(f32, f32) -> f32>(&self, other: &Matrix, f: F) -> Matrix {
+        self.assert_same_shape(other);
+
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        }
+    }
+
+    pub fn add(&self, other: &Matrix) -> Matrix {
+        self.zip_kernel(other, simd::add)
+    }
+
+    pub fn subtract(&self, other: &Matrix) -> Matrix {
+        self.zip_kernel(other, simd::subtract)
+    }
+
+    /// Element-wise (hadamard) product.
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        self.zip_kernel(other, simd::multiply)
+    }
+
+    pub fn scale(&self, coeff: f32) -> Matrix {
+        let mut result = self.clone();
+        simd::scale(&mut result.data, coeff);
+        result
+    }
+
+    pub fn divide(&self, coeff: f32) -> Matrix {
+        let mut result = self.clone();
+        simd::divide_scalar(&mut result.data, coeff);
+        result
+    }
+
+    /// Adds a rows x 1 column (e.g. biases) to every column.
+    pub fn add_column(&self, column: &Matrix) -> Matrix {
+        if column.rows != self.rows || column.cols != 1 {
+            panic!(
+                "Expected a {}x1 column, got {}x{}!",
+                self.rows, column.rows, column.cols
+            );
+        }
+
+        let mut result = self.clone();
+
+        for (i, &bias) in column.data.iter().enumerate() {
+            simd::add_scalar(result.row_mut(i), bias);
+        }
+
+        result
+    }
+
+    /// Sum of every row as a rows x 1 matrix.
+    pub fn row_sum(&self) -> Matrix {
+        Matrix::new(
+            self.rows,
+            1,
+            self.iter_rows().map(|row| row.iter().sum()).collect(),
+        )
+    }
+
+    pub fn col_sum(&self) -> Vec<f32> {
+        let mut result = vec![0.0; self.cols];
+
+        for row in self.iter_rows() {
+            simd::add(&mut result, row);
+        }
+
+        result
+    }
+
+    pub fn col_max(&self) -> Vec<f32> {
+        let mut result = vec![f32::NEG_INFINITY; self.cols];
+
+        for row in self.iter_rows() {
+            simd::max(&mut result, row);
+        }
+
+        result
+    }
+
+    pub fn max(&self) -> f32 {
+        self.data.iter().copied().fold(f32::NEG_INFINITY, f32::max)
+    }
+
+    pub fn min(&self) -> f32 {
+        self.data.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    pub fn sum(&self) -> f32 {
+        self.data.iter().sum()
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.sum() / self.data.len() as f32
+    }
+
+    /// Copies the matrix into one vector per row.
+    pub fn to_nested(&self) -> Vec<Vec<f32>> {
+        self.iter_rows().map(|row| row.to_vec()).collect()
+    }
+
+    /// Applies an in-place `simd` kernel to a copy of `self`.
+    fn zip_kernel(&self, other: &Matrix, kernel: fn(&mut [f32], &[f32])) -> Matrix {
+        self.assert_same_shape(other);
+
+        let mut result = self.clone();
+        kernel(&mut result.data, &other.data);
+        result
+    }
+
+    fn assert_same_shape(&self, other: &Matrix) {
+        if self.shape() != other.shape() {
+            panic!(
+                "Matrix dimensions must match: {}x{} and {}x{}!",
+                self.rows, self.cols, other.rows, other.cols
+            );
+        }
+    }
+}
+
+impl<'a> MatrixView<'a> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn row(&self, i: usize) -> &'a [f32] {
+        if i >= self.rows {
+            panic!(
+                "Row {i} is out of bounds of a view with {} rows!",
+                self.rows
+            );
+        }
+
+        &self.data[i * self.stride..i * self.stride + self.cols]
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        self.row(i)[j]
+    }
+
+    pub fn to_matrix(self) -> Matrix {
+        let mut data = Vec::with_capacity(self.rows * self.cols);
+
+        for i in 0..self.rows {
+            data.extend_from_slice(self.row(i));
+        }
+
+        Matrix::new(self.rows, self.cols, data)
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f32;
+
+    fn index(&self, (i, j): (usize, usize)) -> &f32 {
+        &self.row(i)[j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f32 {
+        &mut self.row_mut(i)[j]
+    }
+}
+
+impl From<&[Vec<f32>]> for Matrix {
+    fn from(nested: &[Vec<f32>]) -> Self {
+        let rows = nested.len();
+        let cols = nested.first().map_or(0, |row| row.len());
+
+        if nested.iter().any(|row| row.len() != cols) {
+            panic!("Every row must have the same number of columns!");
+        }
+
+        Matrix::new(rows, cols, nested.concat())
+    }
+}
+
+impl From<Vec<Vec<f32>>> for Matrix {
+    fn from(nested: Vec<Vec<f32>>) -> Self {
+        Matrix::from(nested.as_slice())
+    }
+}
+
+impl From<&Matrix> for Vec<Vec<f32>> {
+    fn from(matrix: &Matrix) -> Self {
+        matrix.to_nested()
+    }
+}
+
+impl From<Matrix> for Vec<Vec<f32>> {
+    fn from(matrix: Matrix) -> Self {
+        matrix.to_nested()
+    }
+}
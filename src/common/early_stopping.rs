@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+use super::types::NetworkParams;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Stops training once the monitored metric stopped improving, see `EARLY_STOPPING` in
+/// `.env_sample`. The network of the best iteration is kept and returned instead of the last.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EarlyStopping {
+    pub monitor: Monitor,
+    /// Iterations without improvement before training stops.
+    pub patience: usize,
+    /// Smallest change of the metric counting as an improvement.
+    pub min_delta: f32,
+}
+
+/// Metric watched by early stopping, measured on the validation set or on the training set
+/// when there is none.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Monitor {
+    Loss,
+    /// Accuracy in percent, `min_delta` is in percentage points.
+    Accuracy,
+}
+
+impl Monitor {
+    fn improves(&self, value: f32, best: f32, min_delta: f32) -> bool {
+        match self {
+            Monitor::Loss => value < best - min_delta,
+            Monitor::Accuracy => value > best + min_delta,
+        }
+    }
+}
+
+/// Progress of early stopping during a run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EarlyStoppingState {
+    /// Best value of the monitored metric, `None` before the first iteration.
+    pub best: Option<f32>,
+    /// Iterations completed when the best value was measured.
+    pub best_iteration: usize,
+    /// Iterations since the best value.
+    pub wait: usize,
+    /// Copy of the network at the best iteration.
+    pub best_params: NetworkParams,
+}
+
+impl EarlyStoppingState {
+    /// Records the metric of the network after `iteration` completed iterations.
+    pub fn update(
+        &mut self,
+        early_stopping: &EarlyStopping,
+        value: f32,
+        network_params: &NetworkParams,
+        iteration: usize,
+    ) {
+        let improved = match self.best {
+            Some(best) => early_stopping
+                .monitor
+                .improves(value, best, early_stopping.min_delta),
+            None => true,
+        };
+
+        if improved {
+            self.best = Some(value);
+            self.best_iteration = iteration;
+            self.wait = 0;
+            self.best_params = network_params.clone();
+        } else {
+            self.wait += 1;
+        }
+    }
+
+    /// A patience of 0 stops at the first iteration without improvement.
+    pub fn should_stop(&self, early_stopping: &EarlyStopping) -> bool {
+        self.wait >= early_stopping.patience.max(1)
+    }
+}
+
+impl Display for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Monitor::Loss => write!(f, "loss"),
+            Monitor::Accuracy => write!(f, "accuracy"),
+        }
+    }
+}
+
+/// Parses `loss` or `accuracy`, optionally prefixed with `val_`.
+impl FromStr for Monitor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+
+        match name.strip_prefix("val_").unwrap_or(&name) {
+            "loss" => Ok(Monitor::Loss),
+            "accuracy" | "acc" => Ok(Monitor::Accuracy),
+            _ => Err(format!("Unknown early stopping metric: {s}")),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can go wrong while loading data, models or configuration.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Csv(csv::Error),
+    /// A value that isn't a valid number (or activation), rows and columns start at 0.
+    Parse {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    /// (rows, cols) of the two operands.
+    ShapeMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// No predictors were saved in the model directory.
+    MissingModel(PathBuf),
+    InvalidConfig(String),
+    /// Input data that doesn't fit the network, e.g. images with the wrong pixel count.
+    InvalidInput(String),
+    /// A binary model or checkpoint file that can't be decoded, e.g. truncated or with a
+    /// checksum mismatch.
+    CorruptModel(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::Csv(e) => write!(f, "{e}"),
+            Error::Parse { row, column, value } => {
+                write!(f, "Invalid value {value:?} at row {row}, column {column}")
+            }
+            Error::ShapeMismatch { left, right } => write!(
+                f,
+                "Incompatible matrix shapes: {}x{} and {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+            Error::MissingModel(model_dir) => write!(
+                f,
+                "Predictors not found in {}, run `train` first",
+                model_dir.display()
+            ),
+            Error::InvalidConfig(message) => write!(f, "Invalid configuration: {message}"),
+            Error::InvalidInput(message) => write!(f, "Invalid input: {message}"),
+            Error::CorruptModel(message) => write!(f, "Corrupt model file: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Csv(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::Csv(e)
+    }
+}
@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+use super::dense_matrix::Matrix;
+use super::network_functions::{forward_propagation, get_predictions};
+use super::types::NetworkParams;
+use std::fmt::{self, Display};
+
+/// Confusion matrix of a classifier on a labelled dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    /// confusion[actual][predicted] sample counts.
+    pub confusion: Vec<Vec<usize>>,
+}
+
+impl Evaluation {
+    /// labels: 1xm  predictions: one class index per sample
+    pub fn new(labels: &Matrix, predictions: &[usize], classes: usize) -> Self {
+        let mut confusion = vec![vec![0; classes]; classes];
+
+        for (&label, &predicted) in labels.row(0).iter().zip(predictions) {
+            confusion[label as usize][predicted] += 1;
+        }
+
+        Evaluation { confusion }
+    }
+
+    pub fn classes(&self) -> usize {
+        self.confusion.len()
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.confusion.iter().flatten().sum()
+    }
+
+    pub fn correct(&self) -> usize {
+        (0..self.classes()).map(|i| self.confusion[i][i]).sum()
+    }
+
+    /// Percentage of correctly predicted samples, like `get_accuracy`.
+    pub fn accuracy(&self) -> f32 {
+        self.correct() as f32 / self.sample_count() as f32 * 100.0
+    }
+
+    /// Share of the samples predicted as `class` that really are `class`, 0 when it was never predicted.
+    pub fn precision(&self, class: usize) -> f32 {
+        let predicted: usize = self.confusion.iter().map(|row| row[class]).sum();
+        ratio(self.confusion[class][class], predicted)
+    }
+
+    /// Share of the samples of `class` that were predicted as `class`, 0 when there are none.
+    pub fn recall(&self, class: usize) -> f32 {
+        let actual: usize = self.confusion[class].iter().sum();
+        ratio(self.confusion[class][class], actual)
+    }
+
+    /// Harmonic mean of precision and recall.
+    pub fn f1(&self, class: usize) -> f32 {
+        let (precision, recall) = (self.precision(class), self.recall(class));
+
+        if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        }
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+/// Runs the network on a held-out dataset.
+/// labels: 1xm  data: inputs x m
+pub fn evaluate(network_params: &NetworkParams, labels: &Matrix, data: &Matrix) -> Evaluation {
+    let forward_cache = forward_propagation(network_params, data);
+    let output = &forward_cache.last().unwrap().activation;
+
+    Evaluation::new(labels, &get_predictions(output), output.rows())
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Test accuracy: {} ({}/{})",
+            self.accuracy(),
+            self.correct(),
+            self.sample_count()
+        )?;
+
+        writeln!(f, "Confusion matrix (rows: actual, columns: predicted)")?;
+        write!(f, "{:>6}", "")?;
+        for class in 0..self.classes() {
+            write!(f, "{class:>6}")?;
+        }
+        writeln!(f)?;
+        for (class, row) in self.confusion.iter().enumerate() {
+            write!(f, "{class:>6}")?;
+            for count in row {
+                write!(f, "{count:>6}")?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(
+            f,
+            "{:>6}{:>11}{:>11}{:>11}",
+            "class", "precision", "recall", "f1"
+        )?;
+        for class in 0..self.classes() {
+            writeln!(
+                f,
+                "{class:>6}{:>11.4}{:>11.4}{:>11.4}",
+                self.precision(class),
+                self.recall(class),
+                self.f1(class)
+            )?;
+        }
+
+        Ok(())
+    }
+}
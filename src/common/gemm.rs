@@ -0,0 +1,177 @@
+#![allow(dead_code)]
+use super::dense_matrix::Matrix;
+use std::thread::{self, available_parallelism};
+
+/// Edge length of the square tiles the output and the shared dimension are split into.
+pub const BLOCK_SIZE: usize = 64;
+
+/// Default size below which `dot_product_strassen` hands the product to `blocked_dot_product`.
+pub const STRASSEN_THRESHOLD: usize = 128;
+
+/// Products with fewer multiply-adds than this run on the calling thread.
+const PARALLEL_THRESHOLD: usize = 1 << 18;
+
+/// Cache-blocked, multi-threaded matrix product.
+/// The right operand is packed transposed so both operands are read row by row, and the
+/// output rows are split across the available threads. Every output cell still sums over
+/// the shared dimension in order, so the result matches the naive triple loop exactly.
+pub fn blocked_dot_product(matrix_1: &Matrix, matrix_2: &Matrix) -> Matrix {
+    let (m, k) = matrix_1.shape();
+    let n = matrix_2.cols();
+
+    if k != matrix_2.rows() {
+        panic!("The number of columns in the first matrix must be equal to the number of rows in the second matrix!");
+    }
+
+    let mut result = Matrix::zeroes(m, n);
+
+    // an empty shared dimension sums nothing, every cell stays 0
+    if m == 0 || n == 0 || k == 0 {
+        return result;
+    }
+
+    // packed: n x k
+    let packed = matrix_2.transpose();
+
+    let available_threads = if m * n * k < PARALLEL_THRESHOLD {
+        1
+    } else {
+        available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(m)
+    };
+
+    if available_threads == 1 {
+        multiply_rows(
+            matrix_1.as_slice(),
+            packed.as_slice(),
+            k,
+            n,
+            result.as_mut_slice(),
+        );
+        return result;
+    }
+
+    thread::scope(|scope| {
+        let mut remaining = result.as_mut_slice();
+
+        for idx in 0..available_threads {
+            // determine the output rows computed by this thread
+            let begin = m * idx / available_threads;
+            let end = m * (idx + 1) / available_threads;
+
+            let (output, rest) = remaining.split_at_mut((end - begin) * n);
+            remaining = rest;
+
+            let rows = &matrix_1.as_slice()[begin * k..end * k];
+            let packed = packed.as_slice();
+
+            scope.spawn(move || multiply_rows(rows, packed, k, n, output));
+        }
+    });
+
+    result
+}
+
+/// Strassen's algorithm: 7 half-size products per level instead of 8.
+/// Recurses while every dimension is larger than `threshold` and multiplies the leaves with
+/// `blocked_dot_product`. Odd dimensions are padded with a zero row/column at each level.
+/// The additions change the rounding, so results only match the other kernels within tolerance.
+pub fn dot_product_strassen(matrix_1: &Matrix, matrix_2: &Matrix, threshold: usize) -> Matrix {
+    let (m, k) = matrix_1.shape();
+    let n = matrix_2.cols();
+
+    if k != matrix_2.rows() {
+        panic!("The number of columns in the first matrix must be equal to the number of rows in the second matrix!");
+    }
+
+    if m.min(k).min(n) <= threshold.max(1) {
+        return blocked_dot_product(matrix_1, matrix_2);
+    }
+
+    let (half_m, half_k, half_n) = (m.div_ceil(2), k.div_ceil(2), n.div_ceil(2));
+
+    let a_11 = quadrant(matrix_1, 0, 0, half_m, half_k);
+    let a_12 = quadrant(matrix_1, 0, half_k, half_m, half_k);
+    let a_21 = quadrant(matrix_1, half_m, 0, half_m, half_k);
+    let a_22 = quadrant(matrix_1, half_m, half_k, half_m, half_k);
+
+    let b_11 = quadrant(matrix_2, 0, 0, half_k, half_n);
+    let b_12 = quadrant(matrix_2, 0, half_n, half_k, half_n);
+    let b_21 = quadrant(matrix_2, half_k, 0, half_k, half_n);
+    let b_22 = quadrant(matrix_2, half_k, half_n, half_k, half_n);
+
+    let product = |left: Matrix, right: Matrix| dot_product_strassen(&left, &right, threshold);
+
+    let m_1 = product(a_11.add(&a_22), b_11.add(&b_22));
+    let m_2 = product(a_21.add(&a_22), b_11.clone());
+    let m_3 = product(a_11.clone(), b_12.subtract(&b_22));
+    let m_4 = product(a_22.clone(), b_21.subtract(&b_11));
+    let m_5 = product(a_11.add(&a_12), b_22.clone());
+    let m_6 = product(a_21.subtract(&a_11), b_11.add(&b_12));
+    let m_7 = product(a_12.subtract(&a_22), b_21.add(&b_22));
+
+    let c_11 = m_1.add(&m_4).subtract(&m_5).add(&m_7);
+    let c_12 = m_3.add(&m_5);
+    let c_21 = m_2.add(&m_4);
+    let c_22 = m_1.subtract(&m_2).add(&m_3).add(&m_6);
+
+    // stitch the quadrants together, dropping the padding
+    Matrix::from_fn(m, n, |i, j| {
+        let c = match (i < half_m, j < half_n) {
+            (true, true) => &c_11,
+            (true, false) => &c_12,
+            (false, true) => &c_21,
+            (false, false) => &c_22,
+        };
+
+        c[(i % half_m, j % half_n)]
+    })
+}
+
+/// rows x cols block starting at (row_start, col_start), zero padded past the edges.
+fn quadrant(
+    matrix: &Matrix,
+    row_start: usize,
+    col_start: usize,
+    rows: usize,
+    cols: usize,
+) -> Matrix {
+    let mut result = Matrix::zeroes(rows, cols);
+    let copied_cols = cols.min(matrix.cols().saturating_sub(col_start));
+
+    for i in 0..rows.min(matrix.rows().saturating_sub(row_start)) {
+        let source = &matrix.row(row_start + i)[col_start..col_start + copied_cols];
+        result.row_mut(i)[..copied_cols].copy_from_slice(source);
+    }
+
+    result
+}
+
+/// output (rows x n) += rows (rows x k) * packed^T (packed: n x k), tile by tile.
+fn multiply_rows(rows: &[f32], packed: &[f32], k: usize, n: usize, output: &mut [f32]) {
+    let row_count = output.len() / n;
+
+    for j_block in (0..n).step_by(BLOCK_SIZE) {
+        let j_end = (j_block + BLOCK_SIZE).min(n);
+
+        for k_block in (0..k).step_by(BLOCK_SIZE) {
+            let k_end = (k_block + BLOCK_SIZE).min(k);
+
+            for i in 0..row_count {
+                let row = &rows[i * k + k_block..i * k + k_end];
+                let output_row = &mut output[i * n..(i + 1) * n];
+
+                for (j, cell) in output_row.iter_mut().enumerate().take(j_end).skip(j_block) {
+                    let column = &packed[j * k + k_block..j * k + k_end];
+
+                    let mut sum = *cell;
+                    for (left, right) in row.iter().zip(column) {
+                        sum += left * right;
+                    }
+                    *cell = sum;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+#![allow(dead_code)]
+//! Reader for the IDX files MNIST is distributed in (`train-images-idx3-ubyte`,
+//! `train-labels-idx1-ubyte`, ...), raw or gzip-compressed.
+//!
+//! Every file starts with two zero bytes, a type byte (0x08 for unsigned bytes),
+//! the number of dimensions and one big-endian u32 per dimension, followed by the values.
+use super::error::{Error, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+const UNSIGNED_BYTE: u8 = 0x08;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// True when the file starts like an IDX file or is gzip-compressed, so it can't be a CSV.
+pub fn is_idx_file<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let mut header = [0; 4];
+    let read = fs::File::open(path)?.read(&mut header)?;
+
+    Ok(header[..read].starts_with(&GZIP_MAGIC)
+        || (read == 4 && header[..3] == [0, 0, UNSIGNED_BYTE]))
+}
+
+/// One row of pixels (0-255) per image.
+pub fn read_idx_images<P: AsRef<Path>>(path: P) -> Result<Vec<Vec<f32>>> {
+    let path = path.as_ref();
+    let bytes = read_decompressed(path)?;
+    let (dimensions, values) = parse_idx(path, &bytes, 3)?;
+    let pixels = dimensions[1] * dimensions[2];
+
+    if pixels == 0 {
+        return Err(Error::InvalidInput(format!(
+            "{}: images of {}x{} pixels",
+            path.display(),
+            dimensions[1],
+            dimensions[2]
+        )));
+    }
+
+    Ok(values
+        .chunks_exact(pixels)
+        .map(|image| image.iter().map(|&pixel| pixel as f32).collect())
+        .collect())
+}
+
+pub fn read_idx_labels<P: AsRef<Path>>(path: P) -> Result<Vec<f32>> {
+    let path = path.as_ref();
+    let bytes = read_decompressed(path)?;
+    let (_, values) = parse_idx(path, &bytes, 1)?;
+
+    Ok(values.iter().map(|&label| label as f32).collect())
+}
+
+/// Images and their labels in the layout of the CSV datasets, the label followed by
+/// the pixels of the image on every row, ready for `prepare_data`.
+pub fn read_mnist<P: AsRef<Path>, Q: AsRef<Path>>(
+    images_path: P,
+    labels_path: Q,
+) -> Result<Vec<Vec<f32>>> {
+    let images = read_idx_images(images_path)?;
+    let labels = read_idx_labels(labels_path)?;
+
+    if images.len() != labels.len() {
+        return Err(Error::InvalidInput(format!(
+            "{} images but {} labels",
+            images.len(),
+            labels.len()
+        )));
+    }
+
+    Ok(labels
+        .into_iter()
+        .zip(images)
+        .map(|(label, image)| [vec![label], image].concat())
+        .collect())
+}
+
+fn read_decompressed(path: &Path) -> Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(bytes);
+    }
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
+
+/// Checks the header of an unsigned byte IDX file with `dimension_count` dimensions
+/// and returns its dimensions and values.
+fn parse_idx<'a>(
+    path: &Path,
+    bytes: &'a [u8],
+    dimension_count: usize,
+) -> Result<(Vec<usize>, &'a [u8])> {
+    let invalid = |message: String| Error::InvalidInput(format!("{}: {message}", path.display()));
+
+    if bytes.len() < 4 || bytes[..2] != [0, 0] {
+        return Err(invalid("not an IDX file".to_owned()));
+    }
+    if bytes[2] != UNSIGNED_BYTE {
+        return Err(invalid(format!(
+            "unsupported value type 0x{:02x}, expected unsigned bytes",
+            bytes[2]
+        )));
+    }
+    if bytes[3] as usize != dimension_count {
+        return Err(invalid(format!(
+            "expected {dimension_count} dimensions, got {}",
+            bytes[3]
+        )));
+    }
+
+    let header_length = 4 + 4 * dimension_count;
+    if bytes.len() < header_length {
+        return Err(invalid("the header is truncated".to_owned()));
+    }
+
+    let dimensions: Vec<usize> = bytes[4..header_length]
+        .chunks_exact(4)
+        .map(|size| u32::from_be_bytes(size.try_into().unwrap()) as usize)
+        .collect();
+
+    let values = &bytes[header_length..];
+    let expected = dimensions
+        .iter()
+        .try_fold(1usize, |count, size| count.checked_mul(*size))
+        .ok_or_else(|| invalid(format!("the dimensions {dimensions:?} are too large")))?;
+    if values.len() != expected {
+        return Err(invalid(format!(
+            "the dimensions {dimensions:?} need {expected} values, got {}",
+            values.len()
+        )));
+    }
+
+    Ok((dimensions, values))
+}
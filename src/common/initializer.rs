@@ -0,0 +1,107 @@
+#![allow(dead_code)]
+use super::dense_matrix::Matrix;
+use super::matrix::rand_matrix;
+use rand::Rng;
+use rand_distr::{Distribution, Normal, Uniform};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// How the weights and biases of a dense layer are drawn before training.
+/// fan_in is the number of inputs of the layer and fan_out its number of units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Initializer {
+    /// Weights and biases from uniform(-0.5, 0.5) regardless of the layer size.
+    Uniform,
+    /// normal(0, sqrt(2 / fan_in)), suited to relu and its variants.
+    HeNormal,
+    /// uniform(-sqrt(6 / fan_in), sqrt(6 / fan_in))
+    HeUniform,
+    /// normal(0, sqrt(2 / (fan_in + fan_out))), suited to tanh, sigmoid and softmax.
+    XavierNormal,
+    /// uniform(-sqrt(6 / (fan_in + fan_out)), sqrt(6 / (fan_in + fan_out)))
+    XavierUniform,
+    /// normal(0, sqrt(1 / fan_in))
+    LecunNormal,
+    /// uniform(-sqrt(3 / fan_in), sqrt(3 / fan_in))
+    LecunUniform,
+}
+
+impl Initializer {
+    /// Returns the weights (units x inputs) and biases (units x 1) of a new layer.
+    /// Every scheme but `Uniform` starts with zero biases.
+    pub fn init<R: Rng>(&self, units: usize, inputs: usize, rng: &mut R) -> (Matrix, Matrix) {
+        let (fan_in, fan_out) = (inputs as f32, units as f32);
+
+        let weights = match self {
+            Initializer::Uniform => {
+                // weights first, then biases, like the networks trained before initializers existed
+                let weights = Matrix::from(rand_matrix(units, inputs, rng));
+                let biases = Matrix::from(rand_matrix(units, 1, rng));
+                return (weights, biases);
+            }
+            Initializer::HeNormal => sample(units, inputs, normal(2.0 / fan_in), rng),
+            Initializer::HeUniform => sample(units, inputs, uniform(6.0 / fan_in), rng),
+            Initializer::XavierNormal => {
+                sample(units, inputs, normal(2.0 / (fan_in + fan_out)), rng)
+            }
+            Initializer::XavierUniform => {
+                sample(units, inputs, uniform(6.0 / (fan_in + fan_out)), rng)
+            }
+            Initializer::LecunNormal => sample(units, inputs, normal(1.0 / fan_in), rng),
+            Initializer::LecunUniform => sample(units, inputs, uniform(3.0 / fan_in), rng),
+        };
+
+        (weights, Matrix::zeroes(units, 1))
+    }
+}
+
+fn normal(variance: f32) -> Normal<f32> {
+    Normal::new(0.0, variance.sqrt()).unwrap()
+}
+
+/// uniform(-sqrt(limit), sqrt(limit))
+fn uniform(limit: f32) -> Uniform<f32> {
+    Uniform::new_inclusive(-limit.sqrt(), limit.sqrt())
+}
+
+fn sample<D: Distribution<f32>, R: Rng>(rows: usize, cols: usize, dist: D, rng: &mut R) -> Matrix {
+    Matrix::from_fn(rows, cols, |_, _| dist.sample(rng))
+}
+
+impl Display for Initializer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Initializer::Uniform => write!(f, "uniform"),
+            Initializer::HeNormal => write!(f, "he_normal"),
+            Initializer::HeUniform => write!(f, "he_uniform"),
+            Initializer::XavierNormal => write!(f, "xavier_normal"),
+            Initializer::XavierUniform => write!(f, "xavier_uniform"),
+            Initializer::LecunNormal => write!(f, "lecun_normal"),
+            Initializer::LecunUniform => write!(f, "lecun_uniform"),
+        }
+    }
+}
+
+/// Parses names like `he_normal` or `xavier_uniform`, `glorot_*` is an alias of `xavier_*`
+/// and `he` and `lecun` stand for their normal variants.
+impl FromStr for Initializer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "uniform" => Ok(Initializer::Uniform),
+            "he" | "he_normal" => Ok(Initializer::HeNormal),
+            "he_uniform" => Ok(Initializer::HeUniform),
+            "xavier_normal" | "glorot_normal" => Ok(Initializer::XavierNormal),
+            "xavier_uniform" | "glorot_uniform" => Ok(Initializer::XavierUniform),
+            "lecun" | "lecun_normal" => Ok(Initializer::LecunNormal),
+            "lecun_uniform" => Ok(Initializer::LecunUniform),
+            _ => Err(format!("Unknown initializer: {s}")),
+        }
+    }
+}
+
+/// Default initializers for a network, uniform(-0.5, 0.5) for every layer.
+pub fn default_initializers(layer_count: usize) -> Vec<Initializer> {
+    vec![Initializer::Uniform; layer_count]
+}
@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+#![allow(clippy::excessive_precision)]
 
 pub fn get_w_1_test() -> Vec<Vec<f32>> {
     vec![
@@ -1,45 +1,44 @@
 #![allow(dead_code)]
-use crate::common::constants::PREDICTORS_FOLDER;
-use crate::save_to_file;
-
+use super::activation::{default_activations, Activation};
+use super::dense_matrix::Matrix;
+use super::error::{Error, Result};
 use super::matrix::create_vec_from_csv;
-use super::types::NetworkParams;
+use super::model_file::{self, Checkpoint, ModelMetadata};
+use super::types::{Layer, NetworkParams};
 use csv::ReaderBuilder;
 use std::fmt::Display;
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-const FILE_PATH: &str = "results/";
+const ACTIVATIONS_FILE: &str = "activations";
+/// Binary model file written by `save_model`, see `model_file` for its layout.
+pub const MODEL_FILE: &str = "model.rnet";
+/// Latest checkpoint of an unfinished training run, see `save_checkpoint`.
+pub const CHECKPOINT_FILE: &str = "checkpoint.rnet";
 
 pub enum ResultFiles {
-    B1,
-    B2,
-    W1,
-    W2,
+    Weights(usize),
+    Biases(usize),
 }
 
 impl ResultFiles {
-    fn as_str(&self) -> &'static str {
+    fn file_name(&self) -> String {
         match self {
-            ResultFiles::B1 => "b_1",
-            ResultFiles::B2 => "b_2",
-            ResultFiles::W1 => "w_1",
-            ResultFiles::W2 => "w_2",
+            ResultFiles::Weights(layer) => format!("w_{layer}"),
+            ResultFiles::Biases(layer) => format!("b_{layer}"),
         }
     }
-}
 
-const RESULT_FILES: [ResultFiles; 4] = [
-    ResultFiles::B1,
-    ResultFiles::B2,
-    ResultFiles::W1,
-    ResultFiles::W2,
-];
+    fn path(&self, model_dir: &Path) -> PathBuf {
+        model_dir.join(format!("{}.csv", self.file_name()))
+    }
+}
 
-pub fn write_csv<T>(filename: &str, matrix: &Vec<Vec<T>>) -> std::io::Result<()>
+pub fn write_csv<T, P>(filename: P, matrix: &Vec<Vec<T>>) -> std::io::Result<()>
 where
     T: Display,
+    P: AsRef<Path>,
 {
     let mut file = File::create(filename)?;
 
@@ -55,7 +54,7 @@ where
     Ok(())
 }
 
-pub fn read_file_into_vector() -> Vec<Vec<f32>> {
+pub fn read_file_into_vector() -> Result<Vec<Vec<f32>>> {
     let reader = ReaderBuilder::new()
         .has_headers(false)
         .from_reader(io::stdin());
@@ -63,72 +62,230 @@ pub fn read_file_into_vector() -> Vec<Vec<f32>> {
     create_vec_from_csv(reader)
 }
 
-pub fn check_results_exist() -> bool {
-    let mut ready_file_counter = 0;
-
-    for (_, name) in RESULT_FILES.iter().enumerate() {
-        let filename = name.as_str();
-        let file_path = format!("{FILE_PATH}{filename}.csv");
-        let file = Path::new(&file_path);
-
-        if file.exists() {
-            match fs::read_to_string(file) {
-                Ok(content) => {
-                    if !content.is_empty() {
-                        ready_file_counter += 1;
-                    }
-                }
-                Err(e) => {
-                    println!("Error reading file, consider emptying results/ folder in the project root:  {e}");
-                }
-            }
-        }
+/// Reads a headerless CSV file, e.g. a dataset passed with `--input`.
+pub fn read_csv_file<P: AsRef<Path>>(path: P) -> Result<Vec<Vec<f32>>> {
+    let reader = ReaderBuilder::new().has_headers(false).from_path(path)?;
+
+    create_vec_from_csv(reader)
+}
+
+fn is_non_empty_file(file: &Path) -> bool {
+    fs::metadata(file).is_ok_and(|metadata| metadata.is_file() && metadata.len() > 0)
+}
+
+/// Number of consecutive layers (w_1/b_1, w_2/b_2, ...) saved in the model directory.
+fn saved_layer_count(model_dir: &Path) -> usize {
+    let mut layer_count = 0;
+
+    while is_non_empty_file(&ResultFiles::Weights(layer_count + 1).path(model_dir))
+        && is_non_empty_file(&ResultFiles::Biases(layer_count + 1).path(model_dir))
+    {
+        layer_count += 1;
     }
 
-    ready_file_counter == 4
+    layer_count
 }
 
-pub fn load_network_params() -> NetworkParams {
-    let b_1_path = ResultFiles::B1.as_str();
-    let b_2_path = ResultFiles::B2.as_str();
-    let w_1_path = ResultFiles::W1.as_str();
-    let w_2_path = ResultFiles::W2.as_str();
+/// True when the model directory holds a binary model or the CSV layout.
+pub fn check_results_exist(model_dir: &Path) -> bool {
+    model_dir.join(MODEL_FILE).is_file() || saved_layer_count(model_dir) > 0
+}
 
-    let b_1_reader = ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(format!("{FILE_PATH}{b_1_path}.csv"))
-        .unwrap();
-    let b_2_reader = ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(format!("{FILE_PATH}{b_2_path}.csv"))
-        .unwrap();
-    let w_1_reader = ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(format!("{FILE_PATH}{w_1_path}.csv"))
-        .unwrap();
-    let w_2_reader = ReaderBuilder::new()
+/// Activations saved next to the layers, networks saved without them use
+/// relu for the hidden layers and softmax for the output layer.
+fn load_activations(model_dir: &Path, layer_count: usize) -> Result<Vec<Activation>> {
+    let activations_path = model_dir.join(format!("{ACTIVATIONS_FILE}.csv"));
+
+    if !is_non_empty_file(&activations_path) {
+        return Ok(default_activations(layer_count));
+    }
+
+    let mut reader = ReaderBuilder::new()
         .has_headers(false)
-        .from_path(format!("{FILE_PATH}{w_2_path}.csv"))
-        .unwrap();
+        .from_path(activations_path)?;
+
+    let record = reader.records().next().transpose()?.unwrap_or_default();
 
-    let b_1 = create_vec_from_csv(b_1_reader);
-    let b_2 = create_vec_from_csv(b_2_reader);
-    let w_1 = create_vec_from_csv(w_1_reader);
-    let w_2 = create_vec_from_csv(w_2_reader);
+    let activations = record
+        .iter()
+        .enumerate()
+        .map(|(column, name)| {
+            name.parse::<Activation>().map_err(|_| Error::Parse {
+                row: 0,
+                column,
+                value: name.to_owned(),
+            })
+        })
+        .collect::<Result<Vec<Activation>>>()?;
 
-    (w_1, b_1, w_2, b_2)
+    if activations.len() != layer_count {
+        return Err(Error::InvalidInput(format!(
+            "{ACTIVATIONS_FILE}.csv has {} activations for {layer_count} layers",
+            activations.len()
+        )));
+    }
+
+    Ok(activations)
 }
 
-pub fn save_predictors(
-    w_1: Vec<Vec<f32>>,
-    b_1: Vec<Vec<f32>>,
-    w_2: Vec<Vec<f32>>,
-    b_2: Vec<Vec<f32>>,
-) {
-    fs::create_dir_all(PREDICTORS_FOLDER).expect("Already Exists");
+/// Loads the binary model when there is one, otherwise the CSV layout.
+pub fn load_network_params(model_dir: &Path) -> Result<NetworkParams> {
+    if model_dir.join(MODEL_FILE).is_file() {
+        let (network_params, _) = load_model(model_dir)?;
+        return Ok(network_params);
+    }
+
+    load_csv_network_params(model_dir)
+}
+
+fn load_csv_network_params(model_dir: &Path) -> Result<NetworkParams> {
+    let layer_count = saved_layer_count(model_dir);
+
+    if layer_count == 0 {
+        return Err(Error::MissingModel(model_dir.to_path_buf()));
+    }
+
+    let activations = load_activations(model_dir, layer_count)?;
+    let mut network_params: NetworkParams = Vec::with_capacity(layer_count);
+
+    for (layer, activation) in (1..=layer_count).zip(activations) {
+        let weights = Matrix::from(read_csv_file(ResultFiles::Weights(layer).path(model_dir))?);
+        let biases = Matrix::from(read_csv_file(ResultFiles::Biases(layer).path(model_dir))?);
 
-    save_to_file!(w_1);
-    save_to_file!(b_1);
-    save_to_file!(w_2);
-    save_to_file!(b_2);
+        // every layer takes the output of the previous one and has one bias per unit
+        let previous_units = network_params
+            .last()
+            .map(|previous| previous.weights.rows());
+        if previous_units.is_some_and(|units| units != weights.cols()) {
+            return Err(Error::ShapeMismatch {
+                left: weights.shape(),
+                right: network_params.last().unwrap().weights.shape(),
+            });
+        }
+        if biases.shape() != (weights.rows(), 1) {
+            return Err(Error::ShapeMismatch {
+                left: weights.shape(),
+                right: biases.shape(),
+            });
+        }
+
+        network_params.push(Layer {
+            weights,
+            biases,
+            activation,
+        });
+    }
+
+    Ok(network_params)
+}
+
+pub fn save_predictors(network_params: &NetworkParams, model_dir: &Path) -> Result<()> {
+    fs::create_dir_all(model_dir)?;
+    for (i, layer) in network_params.iter().enumerate() {
+        write_csv(
+            ResultFiles::Weights(i + 1).path(model_dir),
+            &layer.weights.to_nested(),
+        )?;
+        write_csv(
+            ResultFiles::Biases(i + 1).path(model_dir),
+            &layer.biases.to_nested(),
+        )?;
+    }
+
+    let activations = vec![network_params
+        .iter()
+        .map(|layer| layer.activation)
+        .collect::<Vec<Activation>>()];
+    write_csv(
+        model_dir.join(format!("{ACTIVATIONS_FILE}.csv")),
+        &activations,
+    )?;
+
+    // remove layers left over from a previously saved, deeper network
+    let mut stale_layer = network_params.len() + 1;
+    while fs::remove_file(ResultFiles::Weights(stale_layer).path(model_dir)).is_ok()
+        | fs::remove_file(ResultFiles::Biases(stale_layer).path(model_dir)).is_ok()
+    {
+        stale_layer += 1;
+    }
+
+    Ok(())
+}
+
+pub fn save_model(
+    network_params: &NetworkParams,
+    metadata: &ModelMetadata,
+    model_dir: &Path,
+) -> Result<()> {
+    fs::create_dir_all(model_dir)?;
+    fs::write(
+        model_dir.join(MODEL_FILE),
+        model_file::encode(network_params, metadata),
+    )?;
+
+    Ok(())
+}
+
+pub fn load_model(model_dir: &Path) -> Result<(NetworkParams, ModelMetadata)> {
+    let path = model_dir.join(MODEL_FILE);
+
+    if !path.is_file() {
+        return Err(Error::MissingModel(model_dir.to_path_buf()));
+    }
+
+    model_file::decode(&fs::read(&path)?).map_err(|e| corrupt(&path, e))
+}
+
+/// Adds the path of the model or checkpoint file to a decoding error.
+fn corrupt(path: &Path, e: Error) -> Error {
+    match e {
+        Error::CorruptModel(message) => {
+            Error::CorruptModel(format!("{}: {message}", path.display()))
+        }
+        e => e,
+    }
+}
+
+/// Converts the CSV layout of a model directory to the binary model file.
+/// The CSV files are kept, the binary file takes precedence when loading.
+pub fn migrate_csv_model(model_dir: &Path) -> Result<()> {
+    save_model(
+        &load_csv_network_params(model_dir)?,
+        &ModelMetadata::default(),
+        model_dir,
+    )
+}
+
+/// Replaces the checkpoint of the model directory. The new one is written next to it first,
+/// so an interrupted write leaves the previous checkpoint intact.
+pub fn save_checkpoint(checkpoint: &Checkpoint, model_dir: &Path) -> Result<()> {
+    let path = model_dir.join(CHECKPOINT_FILE);
+    let partial_path = path.with_extension("rnet.partial");
+
+    fs::create_dir_all(model_dir)?;
+    fs::write(&partial_path, model_file::encode_checkpoint(checkpoint))?;
+    fs::rename(partial_path, path)?;
+
+    Ok(())
+}
+
+pub fn load_checkpoint(model_dir: &Path) -> Result<Checkpoint> {
+    let path = model_dir.join(CHECKPOINT_FILE);
+
+    if !path.is_file() {
+        return Err(Error::InvalidInput(format!(
+            "no checkpoint in {}, train with a checkpoint interval first",
+            model_dir.display()
+        )));
+    }
+
+    model_file::decode_checkpoint(&fs::read(&path)?).map_err(|e| corrupt(&path, e))
+}
+
+/// Removes the checkpoint once the run it belongs to has finished.
+pub fn remove_checkpoint(model_dir: &Path) -> Result<()> {
+    match fs::remove_file(model_dir.join(CHECKPOINT_FILE)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
 }
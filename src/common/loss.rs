@@ -0,0 +1,177 @@
+#![allow(dead_code)]
+use super::activation::Activation;
+use super::dense_matrix::Matrix;
+use super::types::LayerOutput;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+// keeps log() finite when an output activation reaches exactly 0 or 1
+const EPSILON: f32 = 1e-7;
+
+/// Loss function comparing the output layer with the expected (one-hot) labels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Loss {
+    /// Categorical cross-entropy, fused with a stable log-softmax for softmax outputs.
+    CrossEntropy,
+    MeanSquaredError,
+    /// Per output cross-entropy, fused with a stable log-sigmoid for sigmoid outputs.
+    BinaryCrossEntropy,
+    /// Multi-class hinge loss on the raw output scores.
+    Hinge,
+}
+
+impl Loss {
+    /// Scalar loss averaged over the m samples (columns) of `output`.
+    /// activation: output layer activation  output: classes x m  expected: classes x m
+    pub fn compute(&self, activation: Activation, output: &LayerOutput, expected: &Matrix) -> f32 {
+        let sample_count = expected.cols() as f32;
+
+        let total: f32 = match (self, activation) {
+            (Loss::CrossEntropy, Activation::Softmax) => {
+                element_pairs(&log_softmax(&output.z), expected)
+                    .map(|(log_a, y)| -y * log_a)
+                    .sum()
+            }
+            (Loss::CrossEntropy, _) => element_pairs(&output.activation, expected)
+                .map(|(a, y)| -y * a.max(EPSILON).ln())
+                .sum(),
+            (Loss::MeanSquaredError, _) => element_pairs(&output.activation, expected)
+                .map(|(a, y)| (a - y) * (a - y))
+                .sum(),
+            (Loss::BinaryCrossEntropy, Activation::Sigmoid) => element_pairs(&output.z, expected)
+                // -y * log(sigmoid(z)) - (1 - y) * log(1 - sigmoid(z)) rewritten to avoid overflow
+                .map(|(z, y)| z.max(0.0) - z * y + (-z.abs()).exp().ln_1p())
+                .sum(),
+            (Loss::BinaryCrossEntropy, _) => element_pairs(&output.activation, expected)
+                .map(|(a, y)| {
+                    let a = a.clamp(EPSILON, 1.0 - EPSILON);
+                    -y * a.ln() - (1.0 - y) * (1.0 - a).ln()
+                })
+                .sum(),
+            (Loss::Hinge, _) => hinge_margins(&output.activation, expected).sum(),
+        };
+
+        total / sample_count
+    }
+
+    /// Gradient of the summed loss with respect to the output layer's z.
+    /// Back propagation scales it by 1/m.
+    pub fn gradient(
+        &self,
+        activation: Activation,
+        output: &LayerOutput,
+        expected: &Matrix,
+    ) -> Matrix {
+        match (self, activation) {
+            // the softmax and sigmoid jacobians cancel with the loss derivative
+            (Loss::CrossEntropy, Activation::Softmax)
+            | (Loss::BinaryCrossEntropy, Activation::Sigmoid) => {
+                output.activation.subtract(expected)
+            }
+            _ => {
+                let gradient = self.activation_gradient(output, expected);
+                activation.backward(&output.z, &output.activation, &gradient)
+            }
+        }
+    }
+
+    /// Gradient of the summed loss with respect to the output layer's activation.
+    fn activation_gradient(&self, output: &LayerOutput, expected: &Matrix) -> Matrix {
+        let activation = &output.activation;
+
+        match self {
+            Loss::CrossEntropy => activation.zip_map(expected, |a, y| -y / a.max(EPSILON)),
+            Loss::MeanSquaredError => activation.zip_map(expected, |a, y| 2.0 * (a - y)),
+            Loss::BinaryCrossEntropy => activation.zip_map(expected, |a, y| {
+                let a = a.clamp(EPSILON, 1.0 - EPSILON);
+                (a - y) / (a * (1.0 - a))
+            }),
+            Loss::Hinge => {
+                // every violated margin pushes its class score down and the true class up
+                let mut gradient =
+                    hinge_margins(activation, expected)
+                        .map(|margin| if margin > 0.0 { 1.0 } else { 0.0 });
+                let violations = gradient.col_sum();
+
+                for (j, label) in expected_classes(expected).into_iter().enumerate() {
+                    gradient[(label, j)] = -violations[j];
+                }
+
+                gradient
+            }
+        }
+    }
+}
+
+/// Column wise log(softmax(z)), computed as z - max - log(sum(exp(z - max))).
+pub fn log_softmax(z: &Matrix) -> Matrix {
+    let column_maxes = z.col_max();
+    let exp_sums = Matrix::from_fn(z.rows(), z.cols(), |i, j| {
+        (z[(i, j)] - column_maxes[j]).exp()
+    })
+    .col_sum();
+
+    Matrix::from_fn(z.rows(), z.cols(), |i, j| {
+        z[(i, j)] - column_maxes[j] - exp_sums[j].ln()
+    })
+}
+
+/// Index of the 1.0 in every column of a one-hot matrix.
+fn expected_classes(expected: &Matrix) -> Vec<usize> {
+    (0..expected.cols())
+        .map(|j| {
+            (0..expected.rows())
+                .max_by(|&a, &b| expected[(a, j)].total_cmp(&expected[(b, j)]))
+                .unwrap()
+        })
+        .collect()
+}
+
+/// max(0, 1 + score - true class score) for every wrong class, 0 for the true class.
+fn hinge_margins(scores: &Matrix, expected: &Matrix) -> Matrix {
+    let classes = expected_classes(expected);
+
+    Matrix::from_fn(scores.rows(), scores.cols(), |i, j| {
+        if classes[j] == i {
+            0.0
+        } else {
+            (1.0 + scores[(i, j)] - scores[(classes[j], j)]).max(0.0)
+        }
+    })
+}
+
+fn element_pairs<'a>(
+    matrix_1: &'a Matrix,
+    matrix_2: &'a Matrix,
+) -> impl Iterator<Item = (f32, f32)> + 'a {
+    matrix_1
+        .as_slice()
+        .iter()
+        .copied()
+        .zip(matrix_2.as_slice().iter().copied())
+}
+
+impl Display for Loss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Loss::CrossEntropy => write!(f, "cross_entropy"),
+            Loss::MeanSquaredError => write!(f, "mse"),
+            Loss::BinaryCrossEntropy => write!(f, "binary_cross_entropy"),
+            Loss::Hinge => write!(f, "hinge"),
+        }
+    }
+}
+
+impl FromStr for Loss {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cross_entropy" | "categorical_cross_entropy" => Ok(Loss::CrossEntropy),
+            "mse" | "mean_squared_error" => Ok(Loss::MeanSquaredError),
+            "binary_cross_entropy" | "bce" => Ok(Loss::BinaryCrossEntropy),
+            "hinge" => Ok(Loss::Hinge),
+            _ => Err(format!("Unknown loss: {s}")),
+        }
+    }
+}
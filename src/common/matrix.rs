@@ -1,5 +1,9 @@
 #![allow(dead_code)]
-use super::types::NetworkParams;
+use super::activation::Activation;
+use super::error::{Error, Result};
+use super::initializer::Initializer;
+use super::simd;
+use super::types::{Layer, NetworkParams};
 use csv::Reader;
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64;
@@ -14,14 +18,18 @@ pub enum Operation {
     Add,
 }
 
-pub fn dot_product(matrix_1: &[Vec<f32>], matrix_2: &[Vec<f32>]) -> Vec<Vec<f32>> {
+pub fn dot_product(matrix_1: &[Vec<f32>], matrix_2: &[Vec<f32>]) -> Result<Vec<Vec<f32>>> {
     let m1_rows = matrix_1.len();
-    let m1_cols = matrix_1.first().unwrap().len();
+    let m1_cols = matrix_1.first().map_or(0, |row| row.len());
     let m2_rows = matrix_2.len();
-    let m2_cols = matrix_2.first().unwrap().len();
+    let m2_cols = matrix_2.first().map_or(0, |row| row.len());
 
+    // the number of columns in the first matrix must be equal to the number of rows in the second matrix
     if m1_cols != m2_rows {
-        panic!("The number of columns in the first matrix must be equal to the number of rows in the second matrix!");
+        return Err(Error::ShapeMismatch {
+            left: (m1_rows, m1_cols),
+            right: (m2_rows, m2_cols),
+        });
     }
 
     let mut result = vec![vec![0.0; m2_cols]; m1_rows];
@@ -36,22 +44,29 @@ pub fn dot_product(matrix_1: &[Vec<f32>], matrix_2: &[Vec<f32>]) -> Vec<Vec<f32>
         }
     }
 
-    result
+    Ok(result)
 }
 
-pub fn create_vec_from_csv<T: std::io::Read>(mut rdr: Reader<T>) -> Vec<Vec<f32>> {
+pub fn create_vec_from_csv<T: std::io::Read>(mut rdr: Reader<T>) -> Result<Vec<Vec<f32>>> {
     let mut vec = Vec::new();
-    for result in rdr.records() {
-        let record = result.unwrap();
+    for (row, result) in rdr.records().enumerate() {
+        let record = result?;
 
         vec.push(
             record
                 .iter()
-                .map(|field| field.parse::<f32>().unwrap())
-                .collect(),
+                .enumerate()
+                .map(|(column, field)| {
+                    field.trim().parse::<f32>().map_err(|_| Error::Parse {
+                        row,
+                        column,
+                        value: field.to_owned(),
+                    })
+                })
+                .collect::<Result<Vec<f32>>>()?,
         );
     }
-    vec
+    Ok(vec)
 }
 
 pub fn transpose(matrix: &[Vec<f32>]) -> Vec<Vec<f32>> {
@@ -68,21 +83,50 @@ pub fn transpose(matrix: &[Vec<f32>]) -> Vec<Vec<f32>> {
     result
 }
 
-pub fn shuffle_matrix<T>(matrix: &mut [Vec<T>]) {
-    let mut rng = rand::thread_rng();
-    shuffle(matrix, &mut rng);
+/// Random number generator behind weight initialisation and shuffling,
+/// the same seed always gives the same sequence.
+pub fn create_rng(seed: Option<u64>) -> Pcg64 {
+    match seed {
+        Some(seed) => Pcg64::seed_from_u64(seed),
+        None => Pcg64::from_entropy(),
+    }
 }
 
-fn shuffle<T, R: Rng>(matrix: &mut [Vec<T>], rng: &mut R) {
-    let row_count = matrix.len();
-    let range = 0..row_count;
-
-    for i in range.clone() {
-        let j = rng.gen_range(range.clone());
-        matrix.swap(i, j);
+/// Fisher-Yates over `count` positions, every one of the `count!` orders is equally likely.
+/// `swap` exchanges two positions of whatever is shuffled, so several buffers can be shuffled
+/// in lockstep.
+pub fn fisher_yates<R: Rng, F: FnMut(usize, usize)>(count: usize, rng: &mut R, mut swap: F) {
+    for i in (1..count).rev() {
+        // j is drawn from the positions that are not final yet, including i itself
+        let j = rng.gen_range(0..=i);
+        swap(i, j);
     }
 }
 
+pub fn shuffle<T, R: Rng>(items: &mut [T], rng: &mut R) {
+    fisher_yates(items.len(), rng, |i, j| items.swap(i, j));
+}
+
+pub fn shuffle_matrix<T, R: Rng>(matrix: &mut [Vec<T>], rng: &mut R) {
+    shuffle(matrix, rng);
+}
+
+/// Returns the indices `0..count` in random order, a permutation that can shuffle
+/// several matrices the same way without moving their values.
+pub fn shuffled_indices<R: Rng>(count: usize, rng: &mut R) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..count).collect();
+    shuffle(&mut indices, rng);
+    indices
+}
+
+/// Builds a matrix from the given columns of `matrix`, in the order of `indices`.
+pub fn select_columns(matrix: &[Vec<f32>], indices: &[usize]) -> Vec<Vec<f32>> {
+    matrix
+        .iter()
+        .map(|row| indices.iter().map(|&j| row[j]).collect())
+        .collect()
+}
+
 pub fn split_matrix(matrix: &[Vec<f32>], n: usize) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
     let (first_n_rows, last_n_rows) = matrix.split_at(n);
     (first_n_rows.to_vec(), last_n_rows.to_vec())
@@ -95,8 +139,7 @@ where
     matrix.iter().map(|row| row[n].clone()).collect()
 }
 
-pub fn rand_matrix(rows: usize, columns: usize) -> Vec<Vec<f32>> {
-    let mut rng = Pcg64::from_entropy();
+pub fn rand_matrix<R: Rng>(rows: usize, columns: usize, rng: &mut R) -> Vec<Vec<f32>> {
     let mut result = Vec::new();
 
     for _ in 0..rows {
@@ -113,88 +156,106 @@ pub fn rand_matrix(rows: usize, columns: usize) -> Vec<Vec<f32>> {
     result
 }
 
-pub fn create_network_params() -> NetworkParams {
-    let w_1 = rand_matrix(10, 784);
-    let b_1 = rand_matrix(10, 1);
-    let w_2 = rand_matrix(10, 10);
-    let b_2 = rand_matrix(10, 1);
+/// Creates randomly initialised layers for a network whose layer widths are given
+/// in order, starting with the input size, e.g. `[784, 128, 64, 10]`.
+/// `activations` and `initializers` hold one entry per layer after the input.
+pub fn create_network_params<R: Rng>(
+    layer_sizes: &[usize],
+    activations: &[Activation],
+    initializers: &[Initializer],
+    rng: &mut R,
+) -> NetworkParams {
+    if layer_sizes.len() < 2 {
+        panic!("A network needs at least an input and an output layer!");
+    }
 
-    (w_1, b_1, w_2, b_2)
-}
+    if activations.len() != layer_sizes.len() - 1 {
+        panic!("Every layer after the input needs exactly one activation!");
+    }
 
-pub fn linear_op(action: Operation, matrix: &[Vec<f32>], bias: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let row_len = matrix.len();
-    let col_len = matrix.first().unwrap().len();
-    let mut result = vec![vec![0.0; col_len]; row_len];
+    if initializers.len() != activations.len() {
+        panic!("Every layer after the input needs exactly one initializer!");
+    }
 
-    for (i, row) in matrix.iter().enumerate() {
-        let bias_row = bias.get(i).unwrap();
-        let bias_term = bias_row.first().unwrap();
-        for (j, cell) in row.iter().enumerate() {
-            if matches!(action, Operation::Subtract) {
-                result[i][j] = *cell - *bias_term
-            } else {
-                result[i][j] = *cell + *bias_term
+    layer_sizes
+        .windows(2)
+        .zip(activations.iter().zip(initializers))
+        .map(|(sizes, (activation, initializer))| {
+            let (weights, biases) = initializer.init(sizes[1], sizes[0], rng);
+
+            Layer {
+                weights,
+                biases,
+                activation: *activation,
             }
-        }
-    }
-    result
+        })
+        .collect()
 }
 
-pub fn multiply(matrix: &[Vec<f32>], coeff: f32) -> Vec<Vec<f32>> {
-    let row_len = matrix.len();
-    let col_len = matrix.first().unwrap().len();
-    let mut result = vec![vec![0.0; col_len]; row_len];
-
-    for (i, row) in matrix.iter().enumerate() {
-        for (j, cell) in row.iter().enumerate() {
-            result[i][j] = *cell * coeff;
-        }
-    }
+pub fn linear_op(action: Operation, matrix: &[Vec<f32>], bias: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let kernel = match action {
+        Operation::Subtract => simd::subtract_scalar,
+        Operation::Add => simd::add_scalar,
+    };
+
+    matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let bias_term = *bias.get(i).unwrap().first().unwrap();
+            let mut result = row.clone();
+            kernel(&mut result, bias_term);
+            result
+        })
+        .collect()
+}
 
-    result
+pub fn multiply(matrix: &[Vec<f32>], coeff: f32) -> Vec<Vec<f32>> {
+    map_rows(matrix, |row| simd::scale(row, coeff))
 }
 
 pub fn divide(matrix: &[Vec<f32>], coeff: f32) -> Vec<Vec<f32>> {
-    let m = matrix.len();
-    let n = matrix.first().unwrap().len();
-    let mut result = vec![vec![0.0; n]; m];
-
-    for i in 0..m {
-        for j in 0..n {
-            result[i][j] = matrix[i][j] / coeff;
-        }
-    }
-
-    result
+    map_rows(matrix, |row| simd::divide_scalar(row, coeff))
 }
 
 pub fn matrix_multiply(matrix_1: &[Vec<f32>], matrix_2: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let m = matrix_1.len();
-    let n = matrix_1.first().unwrap().len();
-    let mut result = vec![vec![0.0; n]; m];
-
-    for i in 0..m {
-        for j in 0..n {
-            result[i][j] = matrix_1[i][j] * matrix_2[i][j];
-        }
-    }
-
-    result
+    zip_rows(matrix_1, matrix_2, simd::multiply)
 }
 
 pub fn matrix_subtract(matrix_1: &[Vec<f32>], matrix_2: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let m = matrix_1.len();
-    let n = matrix_1.first().unwrap().len();
-    let mut result = vec![vec![0.0; n]; m];
+    zip_rows(matrix_1, matrix_2, simd::subtract)
+}
 
-    for i in 0..m {
-        for j in 0..n {
-            result[i][j] = matrix_1[i][j] - matrix_2[i][j];
-        }
+/// Copies every row and applies an in-place `simd` kernel to it.
+fn map_rows<F: Fn(&mut [f32])>(matrix: &[Vec<f32>], kernel: F) -> Vec<Vec<f32>> {
+    matrix
+        .iter()
+        .map(|row| {
+            let mut result = row.clone();
+            kernel(&mut result);
+            result
+        })
+        .collect()
+}
+
+fn zip_rows(
+    matrix_1: &[Vec<f32>],
+    matrix_2: &[Vec<f32>],
+    kernel: fn(&mut [f32], &[f32]),
+) -> Vec<Vec<f32>> {
+    if matrix_1.len() != matrix_2.len() {
+        panic!("Matrix dimensions must match!");
     }
 
-    result
+    matrix_1
+        .iter()
+        .zip(matrix_2)
+        .map(|(row_1, row_2)| {
+            let mut result = row_1.clone();
+            kernel(&mut result, row_2);
+            result
+        })
+        .collect()
 }
 
 pub fn zeroes(rows: usize, cols: usize) -> Vec<Vec<f32>> {
@@ -218,6 +279,20 @@ pub fn col_sum(matrix: &[Vec<f32>]) -> Vec<f32> {
     result
 }
 
+pub fn col_max(matrix: &[Vec<f32>]) -> Vec<f32> {
+    let mut result = matrix.first().unwrap().clone();
+
+    for row in matrix.iter().skip(1) {
+        for (j, &value) in row.iter().enumerate() {
+            if value > result[j] {
+                result[j] = value;
+            }
+        }
+    }
+
+    result
+}
+
 pub fn matrix_max(m: &[Vec<f32>]) -> f32 {
     let mut max_value = m[0][0];
     for row in m {
@@ -1,9 +1,25 @@
+pub mod activation;
 pub mod canvas;
+pub mod config;
 pub mod console;
 pub mod constants;
+pub mod dataset;
+pub mod dense_matrix;
+pub mod early_stopping;
+pub mod error;
+pub mod evaluation;
+pub mod gemm;
+pub mod idx;
+pub mod initializer;
 pub mod integration_test_vars;
 pub mod io;
+pub mod loss;
 pub mod matrix;
+pub mod model_file;
 pub mod network_functions;
 pub mod numpy_vec;
+pub mod optimizer;
+pub mod regularization;
+pub mod schedule;
+pub mod simd;
 pub mod types;
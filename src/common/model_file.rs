@@ -0,0 +1,430 @@
+#![allow(dead_code)]
+//! Self-describing binary model format, every number is little-endian:
+//!
+//! | field | type |
+//! | ------ | ------ |
+//! | magic | `b"RNET"` |
+//! | format version | u32 |
+//! | iterations, alpha, batch size | u64, f32, u64 |
+//! | loss | u32 length + utf-8 |
+//! | accuracy | f32, NaN when unknown |
+//! | layer count | u32 |
+//! | every layer: activation, rows, cols, weights, biases | u32 length + utf-8, u32, u32, rows x cols f32, rows f32 |
+//! | crc32 of everything above | u32 |
+//!
+//! Checkpoints written during training use the same encoding for their layers:
+//!
+//! | field | type |
+//! | ------ | ------ |
+//! | magic | `b"RCKP"` |
+//! | format version | u32 |
+//! | seed, completed iterations, seed of the next iteration | u64, u64, u64 |
+//! | plateau schedule: learning rate scale, best loss, iterations since | f32, f32, u64 |
+//! | layer count, every layer | like the model file |
+//! | optimizer: kind (0 sgd, 1 momentum, 2 rmsprop, 3 adagrad, 4 adam) | u32 |
+//! | optimizer hyperparameters: momentum and nesterov (0 or 1), decay and epsilon, epsilon or beta_1, beta_2 and epsilon, none for sgd | f32 and u32, 2 x f32, f32, 3 x f32 |
+//! | optimizer time step, buffer count | u64, u32 |
+//! | every buffer: layer count, every layer | like the model file |
+//! | early stopping: best value (NaN when unknown), its iteration, iterations since | f32, u64, u64 |
+//! | early stopping best network: layer count, every layer | like the model file |
+//! | crc32 of everything above | u32 |
+use super::activation::Activation;
+use super::dense_matrix::Matrix;
+use super::early_stopping::EarlyStoppingState;
+use super::error::{Error, Result};
+use super::optimizer::{OptimizerKind, OptimizerState};
+use super::schedule::PlateauState;
+use super::types::{Layer, NetworkParams};
+
+pub const MAGIC: &[u8; 4] = b"RNET";
+pub const CHECKPOINT_MAGIC: &[u8; 4] = b"RCKP";
+pub const FORMAT_VERSION: u32 = 1;
+
+/// How a saved network was trained.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModelMetadata {
+    /// Iterations (or epochs) the network was trained for, 0 when unknown.
+    pub iterations: usize,
+    /// Learning rate, 0 when unknown.
+    pub alpha: f32,
+    /// Mini-batch size, 0 for full-batch training or when unknown.
+    pub batch_size: usize,
+    /// Loss the network was trained with, empty when unknown.
+    pub loss: String,
+    /// Accuracy on the held-out set, `None` when there was none.
+    pub accuracy: Option<f32>,
+}
+
+/// Everything needed to continue an interrupted training run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    /// Seed the dataset was shuffled and the network initialised with.
+    pub seed: u64,
+    /// Iterations (or epochs) completed so far.
+    pub iteration: usize,
+    /// Seed of the generator of the next iteration.
+    pub rng_seed: u64,
+    pub plateau: PlateauState,
+    pub network_params: NetworkParams,
+    /// Optimizer the state belongs to, a run only resumes with the same one.
+    pub optimizer: OptimizerKind,
+    pub optimizer_state: OptimizerState,
+    pub early_stopping: EarlyStoppingState,
+}
+
+pub fn encode(network_params: &NetworkParams, metadata: &ModelMetadata) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+
+    put_u32(&mut bytes, FORMAT_VERSION);
+    put_u64(&mut bytes, metadata.iterations as u64);
+    put_f32(&mut bytes, metadata.alpha);
+    put_u64(&mut bytes, metadata.batch_size as u64);
+    put_str(&mut bytes, &metadata.loss);
+    put_f32(&mut bytes, metadata.accuracy.unwrap_or(f32::NAN));
+
+    put_layers(&mut bytes, network_params);
+    put_checksum(&mut bytes);
+
+    bytes
+}
+
+pub fn decode(bytes: &[u8]) -> Result<(NetworkParams, ModelMetadata)> {
+    let mut reader = ByteReader::open(bytes, MAGIC, "model")?;
+
+    let metadata = ModelMetadata {
+        iterations: reader.u64()? as usize,
+        alpha: reader.f32()?,
+        batch_size: reader.u64()? as usize,
+        loss: reader.string()?,
+        accuracy: Some(reader.f32()?).filter(|accuracy| !accuracy.is_nan()),
+    };
+
+    let network_params = reader.network()?;
+    reader.finish()?;
+
+    Ok((network_params, metadata))
+}
+
+pub fn encode_checkpoint(checkpoint: &Checkpoint) -> Vec<u8> {
+    let mut bytes = CHECKPOINT_MAGIC.to_vec();
+
+    put_u32(&mut bytes, FORMAT_VERSION);
+    put_u64(&mut bytes, checkpoint.seed);
+    put_u64(&mut bytes, checkpoint.iteration as u64);
+    put_u64(&mut bytes, checkpoint.rng_seed);
+    put_f32(&mut bytes, checkpoint.plateau.scale);
+    put_f32(&mut bytes, checkpoint.plateau.best_loss);
+    put_u64(&mut bytes, checkpoint.plateau.wait as u64);
+    put_layers(&mut bytes, &checkpoint.network_params);
+
+    put_optimizer(&mut bytes, &checkpoint.optimizer);
+    put_u64(&mut bytes, checkpoint.optimizer_state.time_step);
+    put_u32(&mut bytes, checkpoint.optimizer_state.buffers.len() as u32);
+    for buffer in &checkpoint.optimizer_state.buffers {
+        put_layers(&mut bytes, buffer);
+    }
+
+    let early_stopping = &checkpoint.early_stopping;
+    put_f32(&mut bytes, early_stopping.best.unwrap_or(f32::NAN));
+    put_u64(&mut bytes, early_stopping.best_iteration as u64);
+    put_u64(&mut bytes, early_stopping.wait as u64);
+    put_layers(&mut bytes, &early_stopping.best_params);
+
+    put_checksum(&mut bytes);
+
+    bytes
+}
+
+pub fn decode_checkpoint(bytes: &[u8]) -> Result<Checkpoint> {
+    let mut reader = ByteReader::open(bytes, CHECKPOINT_MAGIC, "checkpoint")?;
+
+    let seed = reader.u64()?;
+    let iteration = reader.u64()? as usize;
+    let rng_seed = reader.u64()?;
+    let plateau = PlateauState {
+        scale: reader.f32()?,
+        best_loss: reader.f32()?,
+        wait: reader.u64()? as usize,
+    };
+    let network_params = reader.network()?;
+
+    let optimizer = reader.optimizer()?;
+    let time_step = reader.u64()?;
+    let buffers = (0..reader.u32()?)
+        .map(|_| reader.network())
+        .collect::<Result<Vec<NetworkParams>>>()?;
+
+    let early_stopping = EarlyStoppingState {
+        best: Some(reader.f32()?).filter(|best| !best.is_nan()),
+        best_iteration: reader.u64()? as usize,
+        wait: reader.u64()? as usize,
+        best_params: reader.layers()?,
+    };
+
+    reader.finish()?;
+
+    Ok(Checkpoint {
+        seed,
+        iteration,
+        rng_seed,
+        plateau,
+        network_params,
+        optimizer,
+        optimizer_state: OptimizerState { time_step, buffers },
+        early_stopping,
+    })
+}
+
+fn put_layers(bytes: &mut Vec<u8>, network_params: &NetworkParams) {
+    put_u32(bytes, network_params.len() as u32);
+    for layer in network_params {
+        put_str(bytes, &layer.activation.to_string());
+        put_u32(bytes, layer.weights.rows() as u32);
+        put_u32(bytes, layer.weights.cols() as u32);
+        layer
+            .weights
+            .as_slice()
+            .iter()
+            .chain(layer.biases.as_slice())
+            .for_each(|&value| put_f32(bytes, value));
+    }
+}
+
+fn put_optimizer(bytes: &mut Vec<u8>, optimizer: &OptimizerKind) {
+    match *optimizer {
+        OptimizerKind::Sgd => put_u32(bytes, 0),
+        OptimizerKind::Momentum { momentum, nesterov } => {
+            put_u32(bytes, 1);
+            put_f32(bytes, momentum);
+            put_u32(bytes, nesterov as u32);
+        }
+        OptimizerKind::RmsProp { decay, epsilon } => {
+            put_u32(bytes, 2);
+            put_f32(bytes, decay);
+            put_f32(bytes, epsilon);
+        }
+        OptimizerKind::Adagrad { epsilon } => {
+            put_u32(bytes, 3);
+            put_f32(bytes, epsilon);
+        }
+        OptimizerKind::Adam {
+            beta_1,
+            beta_2,
+            epsilon,
+        } => {
+            put_u32(bytes, 4);
+            put_f32(bytes, beta_1);
+            put_f32(bytes, beta_2);
+            put_f32(bytes, epsilon);
+        }
+    }
+}
+
+fn put_checksum(bytes: &mut Vec<u8>) {
+    let checksum = crc32(bytes);
+    put_u32(bytes, checksum);
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_f32(bytes: &mut Vec<u8>, value: f32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(bytes: &mut Vec<u8>, value: &str) {
+    put_u32(bytes, value.len() as u32);
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Checks the magic, the checksum and the version and starts reading after the version.
+    fn open(bytes: &'a [u8], magic: &[u8; 4], kind: &str) -> Result<Self> {
+        if bytes.len() < magic.len() + 8 || &bytes[..magic.len()] != magic {
+            return Err(invalid(&format!("not a rustnet {kind} file")));
+        }
+
+        let (content, checksum) = bytes.split_at(bytes.len() - 4);
+        if crc32(content) != u32::from_le_bytes(checksum.try_into().unwrap()) {
+            return Err(invalid(&format!(
+                "checksum mismatch, the {kind} file is corrupted"
+            )));
+        }
+
+        let mut reader = ByteReader {
+            bytes: content,
+            position: magic.len(),
+        };
+
+        let version = reader.u32()?;
+        if version == 0 {
+            return Err(invalid("format version 0 is not a valid version"));
+        }
+        if version > FORMAT_VERSION {
+            return Err(invalid(&format!(
+                "format version {version} is newer than the supported version {FORMAT_VERSION}"
+            )));
+        }
+
+        Ok(reader)
+    }
+
+    fn finish(&self) -> Result<()> {
+        if self.position != self.bytes.len() {
+            return Err(invalid("unexpected data after the last layer"));
+        }
+
+        Ok(())
+    }
+
+    /// Reads a network with at least one layer.
+    fn network(&mut self) -> Result<NetworkParams> {
+        let network_params = self.layers()?;
+
+        if network_params.is_empty() {
+            return Err(invalid("the network has no layers"));
+        }
+
+        Ok(network_params)
+    }
+
+    /// Reads a possibly empty list of layers, each taking the output of the previous one.
+    fn layers(&mut self) -> Result<NetworkParams> {
+        let layer_count = self.u32()?;
+        // the count isn't trusted for an allocation, a truncated file fails while reading instead
+        let mut network_params: NetworkParams = Vec::new();
+
+        for _ in 0..layer_count {
+            let activation = self
+                .string()?
+                .parse::<Activation>()
+                .map_err(|e| invalid(&e))?;
+            let rows = self.u32()? as usize;
+            let cols = self.u32()? as usize;
+
+            if rows == 0 || cols == 0 {
+                return Err(invalid(&format!(
+                    "layer {} has {rows}x{cols} weights",
+                    network_params.len() + 1
+                )));
+            }
+
+            let previous_units = network_params
+                .last()
+                .map(|previous| previous.weights.rows());
+            if previous_units.is_some_and(|units| units != cols) {
+                return Err(invalid(&format!(
+                    "layer {} takes {cols} inputs but the previous layer has {} units",
+                    network_params.len() + 1,
+                    previous_units.unwrap()
+                )));
+            }
+
+            network_params.push(Layer {
+                weights: Matrix::new(rows, cols, self.f32s(rows * cols)?),
+                biases: Matrix::new(rows, 1, self.f32s(rows)?),
+                activation,
+            });
+        }
+
+        Ok(network_params)
+    }
+
+    fn optimizer(&mut self) -> Result<OptimizerKind> {
+        Ok(match self.u32()? {
+            0 => OptimizerKind::Sgd,
+            1 => OptimizerKind::Momentum {
+                momentum: self.f32()?,
+                nesterov: self.u32()? != 0,
+            },
+            2 => OptimizerKind::RmsProp {
+                decay: self.f32()?,
+                epsilon: self.f32()?,
+            },
+            3 => OptimizerKind::Adagrad {
+                epsilon: self.f32()?,
+            },
+            4 => OptimizerKind::Adam {
+                beta_1: self.f32()?,
+                beta_2: self.f32()?,
+                epsilon: self.f32()?,
+            },
+            other => return Err(invalid(&format!("unknown optimizer kind {other}"))),
+        })
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("the model file is truncated"))?;
+
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32s(&mut self, count: usize) -> Result<Vec<f32>> {
+        let bytes = self.take(
+            count
+                .checked_mul(4)
+                .ok_or_else(|| invalid("layer too large"))?,
+        )?;
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|value| f32::from_le_bytes(value.try_into().unwrap()))
+            .collect())
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let length = self.u32()? as usize;
+
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|e| invalid(&e.to_string()))
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::CorruptModel(message.to_owned())
+}
+
+/// CRC-32 (IEEE 802.3, the one used by zip and png).
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
@@ -1,258 +1,680 @@
 #![allow(dead_code)]
-use super::io::load_network_params;
-use super::matrix::Operation::Add;
-use super::matrix::{
-    col_sum, create_network_params, divide, flip_rotate, get_nth_column, matrix_max,
-    matrix_multiply, multiply, row_sum, shuffle_matrix, split_matrix,
-};
-use super::{
-    matrix::{dot_product, linear_op, matrix_subtract, transpose, zeroes},
-    types::NetworkParams,
-};
-
-pub fn relu(input: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let row_count = input.len();
-    let column_count = input.first().unwrap().len();
-    let mut output = vec![vec![0.0; column_count]; row_count];
-
-    for (i, row) in input.iter().enumerate() {
-        for (j, cell) in row.iter().enumerate() {
-            output[i][j] = if cell > &0.0 { *cell } else { 0.0 }
-        }
-    }
-
+use super::activation::Activation;
+use super::config::TrainingConfig;
+use super::dense_matrix::Matrix;
+use super::early_stopping::{EarlyStoppingState, Monitor};
+use super::error::{Error, Result};
+use super::loss::Loss;
+use super::matrix::{create_network_params, create_rng, flip_rotate, shuffled_indices};
+use super::model_file::Checkpoint;
+use super::optimizer::Optimizer;
+use super::regularization;
+use super::schedule::PlateauState;
+use super::simd;
+use super::types::{Dataset, ForwardCache, Layer, LayerOutput, NetworkParams};
+use rand::Rng;
+use rand_pcg::Pcg64;
+use std::borrow::Cow;
+
+pub fn relu(input: &Matrix) -> Matrix {
+    let mut output = input.clone();
+    simd::relu(output.as_mut_slice());
     output
 }
 
-fn relu_derivative(input: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let row_count = input.len();
-    let column_count = input.first().unwrap().len();
-    let mut output = vec![vec![0.0; column_count]; row_count];
+/// Column wise softmax, every column (sample) is shifted by its own max before
+/// exponentiating so large logits in one sample can't underflow the others.
+pub fn softmax(matrix: &Matrix) -> Matrix {
+    let column_maxes = matrix.col_max();
+    let mut softmax_output = matrix.clone();
 
-    for (i, row) in input.iter().enumerate() {
-        for (j, cell) in row.iter().enumerate() {
-            output[i][j] = if *cell > 0.0 { 1.0 } else { 0.0 }
-        }
+    for i in 0..softmax_output.rows() {
+        simd::subtract(softmax_output.row_mut(i), &column_maxes);
     }
+    softmax_output.map_in_place(f32::exp);
 
-    output
-}
-
-pub fn softmax(matrix: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let mut softmax_output: Vec<Vec<f32>> =
-        vec![vec![0.0; matrix.first().unwrap().len()]; matrix.len()];
+    let column_sums = softmax_output.col_sum();
 
-    let max = matrix_max(matrix);
-
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            softmax_output[i][j] = f32::exp(matrix[i][j] - max);
-        }
+    for i in 0..softmax_output.rows() {
+        simd::divide(softmax_output.row_mut(i), &column_sums);
     }
 
-    let column_sums = col_sum(&softmax_output);
-
     softmax_output
-        .iter()
-        .map(|m_row| {
-            m_row
-                .iter()
-                .zip(&column_sums)
-                .map(|(&cell, column_sum)| cell / column_sum)
-                .collect()
-        })
-        .collect()
 }
 
-pub fn transform_labels_to_network_output(labels: &[Vec<f32>]) -> Vec<Vec<f32>> {
-    let labels_first_col = match labels {
-        [x] => x,
-        _ => panic!("expected single element"),
-    };
+pub fn transform_labels_to_network_output(labels: &Matrix) -> Matrix {
+    one_hot(labels, 10)
+}
 
-    let rows_len = labels_first_col.len();
+/// Encodes a 1xm label matrix as a classes x m matrix with a single 1.0 per column.
+pub fn one_hot(labels: &Matrix, classes: usize) -> Matrix {
+    if labels.rows() != 1 {
+        panic!("expected single element");
+    }
 
-    let mut zeroes_matrix = zeroes(rows_len, 10);
+    let mut result = Matrix::zeroes(classes, labels.cols());
 
-    for (i, label) in labels_first_col.iter().enumerate() {
-        zeroes_matrix[i][*label as usize] = 1.0;
+    for (j, label) in labels.row(0).iter().enumerate() {
+        result[(*label as usize, j)] = 1.0;
     }
 
-    transpose(&zeroes_matrix)
+    result
 }
 
-pub fn get_predictions(matrix: &[Vec<f32>]) -> Vec<usize> {
-    let ncol = matrix.first().unwrap().len();
-    let mut result = vec![0; ncol];
-    for (i, _) in matrix.first().unwrap().iter().enumerate() {
-        let mut max_index = 0;
-        let mut max_value = matrix.first().unwrap()[i];
-        for (j, _) in matrix.iter().enumerate().skip(1) {
-            if matrix[j][i] > max_value {
-                max_index = j;
-                max_value = matrix[j][i];
+pub fn get_predictions(matrix: &Matrix) -> Vec<usize> {
+    let mut result = vec![0; matrix.cols()];
+    let mut max_values = matrix.row(0).to_vec();
+
+    for i in 1..matrix.rows() {
+        for (j, &value) in matrix.row(i).iter().enumerate() {
+            if value > max_values[j] {
+                result[j] = i;
+                max_values[j] = value;
             }
         }
-        result[i] = max_index;
     }
+
     result
 }
 
-pub fn get_accuracy(labels: &[Vec<f32>], prediction: Vec<usize>) -> f32 {
-    let labels_arr = labels.first().unwrap();
-    let mut accuracy = 0.0;
-    for (i, cell) in labels_arr.iter().enumerate() {
-        if *cell as usize == prediction[i] {
-            accuracy += 1.0
-        }
-    }
-
-    accuracy / labels_arr.len() as f32 * 100.0
+pub fn get_accuracy(labels: &Matrix, prediction: Vec<usize>) -> f32 {
+    count_correct(labels, &prediction) as f32 / labels.cols() as f32 * 100.0
 }
 
-pub fn forward_propagation(
-    network_params: NetworkParams,
-    input_image: &[Vec<f32>],
-) -> NetworkParams {
-    //m is the input size
-    // w_1: 10x784  b_1: 10x1  w_2: 10x10  b2: 10x1
-    let (w_1, b_1, w_2, b_2) = network_params;
-
-    // weighted_input:10xm input_image: 784xm w_1: 10x784
-    let weighted_input = dot_product(&w_1, input_image);
+pub fn count_correct(labels: &Matrix, prediction: &[usize]) -> usize {
+    labels
+        .row(0)
+        .iter()
+        .zip(prediction)
+        .filter(|(cell, predicted)| **cell as usize == **predicted)
+        .count()
+}
 
-    // z_1: 10xm weighted_input: 10xm b_1: 10x1
-    let z_1 = linear_op(Add, &weighted_input, &b_1);
+/// Forward propagation in inference mode, e.g. to predict or evaluate, dropout is disabled.
+pub fn forward_propagation(network_params: &NetworkParams, input_image: &Matrix) -> ForwardCache {
+    forward::<Pcg64>(network_params, input_image, None)
+}
 
-    // activation_1: 10xm z_1:10xm
-    let activation_1 = relu(&z_1);
+/// Forward propagation in training mode, dropping the units of every hidden layer with the
+/// rate of that layer in `dropout`. The masks are kept in the cache for back propagation.
+pub fn forward_propagation_with_dropout<R: Rng>(
+    network_params: &NetworkParams,
+    input_image: &Matrix,
+    dropout: &[f32],
+    rng: &mut R,
+) -> ForwardCache {
+    forward(network_params, input_image, Some((dropout, rng)))
+}
 
-    // weighted_l1: 10xm activation_1: 10xm w_2: 10x10
-    let weighted_l1 = dot_product(&w_2, &activation_1);
+fn forward<R: Rng>(
+    network_params: &NetworkParams,
+    input_image: &Matrix,
+    mut dropout: Option<(&[f32], &mut R)>,
+) -> ForwardCache {
+    //m is the input size
+    let mut forward_cache: ForwardCache = Vec::with_capacity(network_params.len());
+
+    for (l, layer) in network_params.iter().enumerate() {
+        // input: inputs x m  (the image for the first layer, the previous output otherwise)
+        let input = match forward_cache.last() {
+            Some(previous) => previous.output(),
+            None => Cow::Borrowed(input_image),
+        };
+
+        // weighted_input: units x m  input: inputs x m  weights: units x inputs
+        let weighted_input = layer.weights.dot(&input);
+
+        // z: units x m  weighted_input: units x m  biases: units x 1
+        let z = weighted_input.add_column(&layer.biases);
+
+        // activation: units x m  z: units x m
+        let activation = layer.activation.forward(&z);
+
+        // dropout_mask: units x m, the output layer has no rate and is never dropped
+        let dropout_mask = match &mut dropout {
+            Some((rates, rng)) => match rates.get(l) {
+                Some(&rate) if rate > 0.0 => Some(dropout_mask(activation.shape(), rate, *rng)),
+                _ => None,
+            },
+            None => None,
+        };
+
+        forward_cache.push(LayerOutput {
+            z,
+            activation,
+            dropout_mask,
+        });
+    }
 
-    // z_2: 10xm weighted_l1: 10xm b2: 10x1
-    let z_2 = linear_op(Add, &weighted_l1, &b_2);
+    forward_cache
+}
 
-    // activation_2: 10xm z_2: 10xm
-    let activation_2 = softmax(&z_2);
+/// Inverted dropout mask, units are dropped with probability `rate` and the kept ones scaled by
+/// 1 / (1 - rate) so the expected output matches inference.
+fn dropout_mask<R: Rng>((rows, cols): (usize, usize), rate: f32, rng: &mut R) -> Matrix {
+    let scale = 1.0 / (1.0 - rate);
 
-    // z_1: 10xm  activation_1: 10xm  z_2: 10xm  activation_2: 10xm
-    (z_1, activation_1, z_2, activation_2)
+    Matrix::from_fn(rows, cols, |_, _| match rng.gen::<f32>() < rate {
+        true => 0.0,
+        false => scale,
+    })
 }
 
 pub fn back_propagation(
-    network_params: NetworkParams,
-    w_2: Vec<Vec<f32>>,
-    labels: Vec<Vec<f32>>,
-    input_image: &[Vec<f32>],
+    network_params: &NetworkParams,
+    forward_cache: &ForwardCache,
+    labels: &Matrix,
+    input_image: &Matrix,
+    loss: Loss,
 ) -> NetworkParams {
     //m is the input size
 
-    // z_1: 10xm  activation_1: 10xm  activation_2: 10xm
-    let (z_1, activation_1, _z_2, activation_2) = network_params;
-
     // labels: 1xm
-    let m_inverse = 1.0 / (labels.first().unwrap().len() as f32);
+    let m_inverse = 1.0 / (labels.cols() as f32);
+
+    let output = forward_cache.last().unwrap();
 
-    // expected_labels: 10xm  labels: 1xm
-    let expected_labels = transform_labels_to_network_output(&labels);
+    // expected_labels: classes x m  labels: 1xm
+    let expected_labels = one_hot(labels, output.activation.rows());
 
-    // delta_z_2: 10xm  activation_2: 10xm  expected_labels: 10xm
-    let delta_z_2 = matrix_subtract(&activation_2, &expected_labels);
+    // delta_z: classes x m  output: classes x m  expected_labels: classes x m
+    let mut delta_z = loss.gradient(
+        network_params.last().unwrap().activation,
+        output,
+        &expected_labels,
+    );
 
-    // transposed_a_1: mx10  activation_1: 10xm
-    let transposed_a_1 = transpose(&activation_1);
+    let mut gradients: NetworkParams = Vec::with_capacity(network_params.len());
 
-    // delta_w_2: 10x10  delta_z_2: 10xm  transposed_a_1: mx10  m_inverse: f32
-    let delta_w_2 = multiply(&dot_product(&delta_z_2, &transposed_a_1), m_inverse);
+    for l in (0..network_params.len()).rev() {
+        // previous_activation: inputs x m, after dropout
+        let previous_activation = match l {
+            0 => Cow::Borrowed(input_image),
+            _ => forward_cache[l - 1].output(),
+        };
 
-    // sum_delta_z_2: 10x1  delta_z_2: 10xm
-    let sum_delta_z_2 = row_sum(&delta_z_2);
+        // delta_w: units x inputs  delta_z: units x m  previous_activation^T: m x inputs
+        let delta_w = delta_z
+            .dot(&previous_activation.transpose())
+            .scale(m_inverse);
 
-    // delta_b_2: 10x1  sum_delta_z_2: 10x1  m_inverse: f32
-    let delta_b_2 = multiply(&sum_delta_z_2, m_inverse);
+        // delta_b: units x 1  delta_z: units x m
+        let delta_b = delta_z.row_sum().scale(m_inverse);
 
-    // dot_w_2_d_z_2: 10xm  w_2: 10x10  delta_z_2: 10xm
-    let dot_w_2_d_z_2 = dot_product(&transpose(&w_2), &delta_z_2);
+        if l > 0 {
+            // dot_w_d_z: inputs x m  weights^T: inputs x units  delta_z: units x m
+            let mut dot_w_d_z = network_params[l].weights.transpose().dot(&delta_z);
 
-    // deriv_z_1: 10xm  z_1: 10xm
-    let deriv_z_1 = relu_derivative(&z_1);
+            // dropped units pass no gradient back, kept ones are scaled like in forward
+            let previous = &forward_cache[l - 1];
+            if let Some(mask) = &previous.dropout_mask {
+                dot_w_d_z = dot_w_d_z.multiply(mask);
+            }
 
-    // delta_z_1: 10xm  dot_w_2_d_z_2: 10xm  deriv_z_1: 10xm
-    let delta_z_1 = matrix_multiply(&dot_w_2_d_z_2, &deriv_z_1);
+            // delta_z: inputs x m  dot_w_d_z: inputs x m  previous layer output: inputs x m
+            delta_z = network_params[l - 1].activation.backward(
+                &previous.z,
+                &previous.activation,
+                &dot_w_d_z,
+            );
+        }
 
-    // delta_w_1: 10x784  delta_z_1: 10xm  input_image: 784xm  m_inverse: f32
-    let delta_w_1 = multiply(&dot_product(&delta_z_1, &transpose(input_image)), m_inverse);
+        gradients.push(Layer {
+            weights: delta_w,
+            biases: delta_b,
+            activation: network_params[l].activation,
+        });
+    }
 
-    // delta_b_1: 10x1  delta_z_1: 10xm  m_inverse: f32
-    let delta_b_1 = multiply(&row_sum(&delta_z_1), m_inverse);
+    gradients.reverse();
 
-    // delta_w_1: 10x784  delta_b_1: 10x1  delta_w_2: 10x10  delta_b_2: 10x1
-    (delta_w_1, delta_b_1, delta_w_2, delta_b_2)
+    gradients
 }
 
-pub fn train(
-    train_labels: Vec<Vec<f32>>,
-    train_data: Vec<Vec<f32>>,
-    iterations: usize,
-    alpha: f32,
-) -> NetworkParams {
-    let (mut w_1, mut b_1, mut w_2, mut b_2) = create_network_params();
+/// Loss and correctly predicted sample count of a single training step.
+struct StepResult {
+    loss: f32,
+    correct: usize,
+}
 
-    for i in 0..iterations {
-        let forward_prop = forward_propagation(
-            (w_1.clone(), b_1.clone(), w_2.clone(), b_2.clone()),
-            &train_data.clone(),
+/// Runs forward and back propagation on a single batch and updates the network in place.
+/// `rng` draws the dropout masks.
+fn train_step<R: Rng>(
+    network_params: &mut NetworkParams,
+    optimizer: &mut dyn Optimizer,
+    labels: &Matrix,
+    data: &Matrix,
+    config: &TrainingConfig,
+    alpha: f32,
+    rng: &mut R,
+) -> StepResult {
+    let forward_cache =
+        forward_propagation_with_dropout(network_params, data, &config.dropout, rng);
+
+    let output = forward_cache.last().unwrap();
+    let output_activation = network_params.last().unwrap().activation;
+    let expected_labels = one_hot(labels, output.activation.rows());
+    let loss = config
+        .loss
+        .compute(output_activation, output, &expected_labels)
+        + regularization::penalty(
+            network_params,
+            &config.regularization,
+            config.regularize_biases,
         );
 
-        let (delta_w_1, delta_b_1, delta_w_2, delta_b_2) = back_propagation(
-            forward_prop.clone(),
-            w_2.clone(),
-            train_labels.clone(),
-            &train_data.clone(),
+    let mut gradients = back_propagation(network_params, &forward_cache, labels, data, config.loss);
+    regularization::add_penalty_gradients(
+        network_params,
+        &mut gradients,
+        &config.regularization,
+        config.regularize_biases,
+    );
+
+    optimizer.step(network_params, &gradients, alpha);
+    regularization::apply_constraints(
+        network_params,
+        &config.regularization,
+        config.regularize_biases,
+        alpha,
+    );
+
+    let prediction = get_predictions(&output.activation);
+
+    StepResult {
+        loss,
+        correct: count_correct(labels, &prediction),
+    }
+}
+
+/// Loss and accuracy (in percent) of the network on a labelled set without training on it,
+/// e.g. a validation set.
+pub fn compute_metrics(
+    network_params: &NetworkParams,
+    labels: &Matrix,
+    data: &Matrix,
+    loss: Loss,
+) -> (f32, f32) {
+    let forward_cache = forward_propagation(network_params, data);
+
+    let output = forward_cache.last().unwrap();
+    let output_activation = network_params.last().unwrap().activation;
+    let expected_labels = one_hot(labels, output.activation.rows());
+    let accuracy = get_accuracy(labels, get_predictions(&output.activation));
+
+    (
+        loss.compute(output_activation, output, &expected_labels),
+        accuracy,
+    )
+}
+
+/// Network, optimizer and progress of a training run, everything a checkpoint holds.
+pub struct TrainingState {
+    pub network_params: NetworkParams,
+    pub optimizer: Box<dyn Optimizer>,
+    /// Iterations (or epochs) completed so far.
+    pub iteration: usize,
+    /// Seed of the generator of the next iteration. Every iteration draws the seed of the
+    /// following one, so the random state between iterations fits in a checkpoint.
+    pub rng_seed: u64,
+    /// Progress of a reduce-on-plateau learning rate schedule.
+    pub plateau: PlateauState,
+    pub early_stopping: EarlyStoppingState,
+}
+
+impl TrainingState {
+    /// Freshly initialised network, `rng` draws the weights and the seed of the first iteration.
+    pub fn new<R: Rng>(config: &TrainingConfig, rng: &mut R) -> Self {
+        let network_params = create_network_params(
+            &config.layer_sizes,
+            &config.activations,
+            &config.initializers,
+            rng,
         );
 
-        w_1 = matrix_subtract(&w_1, &multiply(&delta_w_1, alpha));
-        b_1 = matrix_subtract(&b_1, &multiply(&delta_b_1, alpha));
-        w_2 = matrix_subtract(&w_2, &multiply(&delta_w_2, alpha));
-        b_2 = matrix_subtract(&b_2, &multiply(&delta_b_2, alpha));
-
-        println!("Iteration: {}", i + 1);
-        let prediction = get_predictions(&forward_prop.3.clone());
-        println!(
-            "Accuracy: {}",
-            get_accuracy(&train_labels.clone(), prediction)
-        )
+        TrainingState {
+            network_params,
+            optimizer: config.optimizer.build(),
+            iteration: 0,
+            rng_seed: rng.gen(),
+            plateau: PlateauState::default(),
+            early_stopping: EarlyStoppingState::default(),
+        }
     }
 
-    (w_1, b_1, w_2, b_2)
+    /// Snapshot of the run, `seed` is the one the dataset was shuffled with.
+    pub fn checkpoint(&self, seed: u64) -> Checkpoint {
+        Checkpoint {
+            seed,
+            iteration: self.iteration,
+            rng_seed: self.rng_seed,
+            plateau: self.plateau,
+            early_stopping: self.early_stopping.clone(),
+            network_params: self.network_params.clone(),
+            optimizer: self.optimizer.kind(),
+            optimizer_state: self.optimizer.state(),
+        }
+    }
+
+    /// Continues a checkpointed run, `config` must describe the same network and optimizer.
+    pub fn resume(checkpoint: Checkpoint, config: &TrainingConfig) -> Result<Self> {
+        let shape = |network_params: &NetworkParams| -> Vec<(usize, usize)> {
+            network_params
+                .iter()
+                .map(|layer| layer.weights.shape())
+                .collect()
+        };
+        let expected: Vec<(usize, usize)> = config
+            .layer_sizes
+            .windows(2)
+            .map(|sizes| (sizes[1], sizes[0]))
+            .collect();
+        let activations: Vec<Activation> = checkpoint
+            .network_params
+            .iter()
+            .map(|layer| layer.activation)
+            .collect();
+
+        if shape(&checkpoint.network_params) != expected || activations != config.activations {
+            return Err(Error::InvalidConfig(
+                "the checkpoint was saved with different layers or activations".to_owned(),
+            ));
+        }
+        if checkpoint.optimizer != config.optimizer {
+            return Err(Error::InvalidConfig(format!(
+                "the checkpoint was saved with the optimizer {:?}, not {:?}",
+                checkpoint.optimizer, config.optimizer
+            )));
+        }
+        if checkpoint
+            .optimizer_state
+            .buffers
+            .iter()
+            .chain([&checkpoint.early_stopping.best_params])
+            .any(|buffer| !buffer.is_empty() && shape(buffer) != expected)
+        {
+            return Err(Error::InvalidInput(
+                "the optimizer or early stopping state of the checkpoint doesn't fit its network"
+                    .to_owned(),
+            ));
+        }
+
+        let mut optimizer = config.optimizer.build();
+        optimizer.load_state(checkpoint.optimizer_state)?;
+
+        Ok(TrainingState {
+            network_params: checkpoint.network_params,
+            optimizer,
+            iteration: checkpoint.iteration,
+            rng_seed: checkpoint.rng_seed,
+            plateau: checkpoint.plateau,
+            early_stopping: checkpoint.early_stopping,
+        })
+    }
+}
+
+/// `rng` initialises the weights and seeds the shuffling of the mini-batches, see `create_rng`.
+pub fn train<R: Rng>(
+    train_labels: Matrix,
+    train_data: Matrix,
+    config: &TrainingConfig,
+    rng: &mut R,
+) -> NetworkParams {
+    let mut state = TrainingState::new(config, rng);
+
+    // nothing can fail without a callback that does
+    continue_training(&mut state, &train_labels, &train_data, None, config, |_| {
+        Ok(())
+    })
+    .unwrap();
+
+    state.network_params
 }
 
-pub fn prepare_data(mut dev_set: Vec<Vec<f32>>) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
-    shuffle_matrix(&mut dev_set);
+/// Trains from `state.iteration` up to `config.iterations`, calling `after_iteration` after
+/// every iteration (or epoch), e.g. to write checkpoints. Stops at the first error it returns.
+/// A run resumed from a saved state ends with the same network as an uninterrupted one.
+/// The loss and accuracy on `validation_set` (labels, data) are logged and drive a plateau
+/// schedule and early stopping, which ends the run with the network of its best iteration.
+pub fn continue_training<F: FnMut(&TrainingState) -> Result<()>>(
+    state: &mut TrainingState,
+    train_labels: &Matrix,
+    train_data: &Matrix,
+    validation_set: Option<(&Matrix, &Matrix)>,
+    config: &TrainingConfig,
+    mut after_iteration: F,
+) -> Result<()> {
+    let sample_count = train_labels.cols();
+
+    let stopped = |state: &TrainingState| match &config.early_stopping {
+        Some(early_stopping) => state.early_stopping.should_stop(early_stopping),
+        None => false,
+    };
 
-    let transposed_dev_matrix = transpose(&dev_set);
+    while state.iteration < config.iterations && !stopped(state) {
+        let i = state.iteration;
+        let mut rng = create_rng(Some(state.rng_seed));
+        let alpha = config
+            .schedule
+            .alpha(config.alpha, config.warmup, i, &state.plateau);
+
+        let (loss, correct) = match config.batch_size {
+            None => {
+                println!("Iteration: {}", i + 1);
+
+                let step = train_step(
+                    &mut state.network_params,
+                    state.optimizer.as_mut(),
+                    train_labels,
+                    train_data,
+                    config,
+                    alpha,
+                    &mut rng,
+                );
+
+                (step.loss, step.correct)
+            }
+            Some(batch_size) => {
+                println!("Epoch: {}", i + 1);
+
+                let mut loss_sum = 0.0;
+                let mut correct = 0;
+
+                for batch in shuffled_indices(sample_count, &mut rng).chunks(batch_size) {
+                    let batch_labels = train_labels.select_columns(batch);
+                    let batch_data = train_data.select_columns(batch);
+
+                    let step = train_step(
+                        &mut state.network_params,
+                        state.optimizer.as_mut(),
+                        &batch_labels,
+                        &batch_data,
+                        config,
+                        alpha,
+                        &mut rng,
+                    );
+
+                    loss_sum += step.loss * batch.len() as f32;
+                    correct += step.correct;
+                }
+
+                (loss_sum / sample_count as f32, correct)
+            }
+        };
+
+        // in mini-batch mode these are running values over all batches of the epoch
+        println!("Loss: {loss}");
+        let accuracy = correct as f32 / sample_count as f32 * 100.0;
+        println!("Accuracy: {accuracy}");
+        println!("Learning rate: {alpha}");
+
+        // without a validation set the schedule and early stopping follow the training metrics
+        let (monitored_loss, monitored_accuracy) = match validation_set {
+            Some((labels, data)) => {
+                let (validation_loss, validation_accuracy) =
+                    compute_metrics(&state.network_params, labels, data, config.loss);
+                let validation_loss = validation_loss
+                    + regularization::penalty(
+                        &state.network_params,
+                        &config.regularization,
+                        config.regularize_biases,
+                    );
+                println!("Validation loss: {validation_loss}");
+                println!("Validation accuracy: {validation_accuracy}");
+                (validation_loss, validation_accuracy)
+            }
+            None => (loss, accuracy),
+        };
+
+        state.plateau.update(&config.schedule, monitored_loss);
+        state.iteration += 1;
+
+        if let Some(early_stopping) = &config.early_stopping {
+            let value = match early_stopping.monitor {
+                Monitor::Loss => monitored_loss,
+                Monitor::Accuracy => monitored_accuracy,
+            };
+            state.early_stopping.update(
+                early_stopping,
+                value,
+                &state.network_params,
+                state.iteration,
+            );
+        }
 
-    let (train_labels, dev_data) = split_matrix(&transposed_dev_matrix, 1);
+        state.rng_seed = rng.gen();
+        after_iteration(state)?;
+    }
 
-    let train_data = divide(&dev_data, 255.0);
+    if let Some(early_stopping) = &config.early_stopping {
+        if stopped(state) {
+            println!(
+                "No {} improvement for {} iterations, stopping early",
+                early_stopping.monitor, state.early_stopping.wait
+            );
+        }
+        if !state.early_stopping.best_params.is_empty() {
+            println!(
+                "Keeping the network of iteration {}",
+                state.early_stopping.best_iteration
+            );
+            state.network_params = state.early_stopping.best_params.clone();
+        }
+    }
 
-    (train_labels, train_data)
+    Ok(())
 }
 
-pub fn predict(input: Vec<Vec<f32>>) -> String {
+/// Shuffles the raw dataset (one sample per row, label first) and returns the
+/// labels (1xm) and the pixel data scaled to 0..1 (784xm).
+pub fn prepare_data<R: Rng>(dev_set: Vec<Vec<f32>>, rng: &mut R) -> (Matrix, Matrix) {
+    let samples = Matrix::from(dev_set);
+    // the samples are read in shuffled order while transposing instead of being moved first
+    let order = shuffled_indices(samples.rows(), rng);
+
+    let labels = Matrix::from_fn(1, order.len(), |_, j| samples[(order[j], 0)]);
+    let data = Matrix::from_fn(samples.cols().saturating_sub(1), order.len(), |i, j| {
+        samples[(order[j], i + 1)] / 255.0
+    });
+
+    (labels, data)
+}
+
+/// Shuffles samples stored column-wise (labels: 1xm  data: inputs x m) with one permutation
+/// of their indices, so every label stays with its sample.
+pub fn shuffle_samples<R: Rng>(labels: &Matrix, data: &Matrix, rng: &mut R) -> (Matrix, Matrix) {
+    let order = shuffled_indices(labels.cols(), rng);
+
+    (labels.select_columns(&order), data.select_columns(&order))
+}
+
+/// Checks a labelled dataset against a network whose first layer has weights of the shape
+/// `input_layer` (units x inputs) and whose output layer has `classes` units.
+/// labels: 1xm  data: inputs x m
+pub fn check_dataset(
+    labels: &Matrix,
+    data: &Matrix,
+    input_layer: (usize, usize),
+    classes: usize,
+) -> Result<()> {
+    if data.cols() == 0 {
+        return Err(Error::InvalidInput("the dataset has no samples".to_owned()));
+    }
+    if data.rows() != input_layer.1 {
+        return Err(Error::ShapeMismatch {
+            left: input_layer,
+            right: data.shape(),
+        });
+    }
+
+    let invalid = labels
+        .row(0)
+        .iter()
+        .position(|label| label.fract() != 0.0 || *label < 0.0 || *label >= classes as f32);
+    if let Some(sample) = invalid {
+        return Err(Error::InvalidInput(format!(
+            "label {} of sample {sample} is not one of the {classes} classes of the network",
+            labels[(0, sample)]
+        )));
+    }
+
+    Ok(())
+}
+
+/// Moves the last `validation_split` share of the (already shuffled) samples into a held-out set.
+/// Returns the training labels and data followed by the held-out ones, `None` when no split was
+/// requested. Fails when either part would be left without samples.
+pub fn split_dataset(
+    labels: Matrix,
+    data: Matrix,
+    validation_split: f32,
+) -> Result<(Dataset, Option<Dataset>)> {
+    if !(0.0..1.0).contains(&validation_split) {
+        return Err(Error::InvalidConfig(format!(
+            "the validation split must be between 0 and 1, got {validation_split}"
+        )));
+    }
+
+    if validation_split == 0.0 {
+        return Ok(((labels, data), None));
+    }
+
+    let sample_count = labels.cols();
+    let test_count = (sample_count as f32 * validation_split).round() as usize;
+    let train_count = sample_count - test_count;
+
+    if test_count == 0 || train_count == 0 {
+        return Err(Error::InvalidInput(format!(
+            "a validation split of {validation_split} leaves {train_count} training and \
+             {test_count} held-out samples of {sample_count}"
+        )));
+    }
+
+    let split = |matrix: &Matrix, columns: std::ops::Range<usize>| {
+        matrix.view(0..matrix.rows(), columns).to_matrix()
+    };
+
+    Ok((
+        (split(&labels, 0..train_count), split(&data, 0..train_count)),
+        Some((
+            split(&labels, train_count..sample_count),
+            split(&data, train_count..sample_count),
+        )),
+    ))
+}
+
+pub fn predict(input: Vec<Vec<f32>>, network_params: &NetworkParams) -> String {
     let mut matrix = input;
 
     // this is needed coz the coordinates received from console engine are flipped and rotated 90deg
     flip_rotate(&mut matrix);
 
-    let flat_array = transpose(&[matrix.concat()]);
-
-    let (w_1, b_1, w_2, b_2) = load_network_params();
+    let pixels = matrix.concat();
+    let flat_array = Matrix::new(pixels.len(), 1, pixels);
 
-    let (_, _, _, ac2) = forward_propagation((w_1, b_1, w_2, b_2), &flat_array);
+    let forward_cache = forward_propagation(network_params, &flat_array);
 
-    let col = get_nth_column(&ac2, 0);
+    let col = forward_cache.last().unwrap().activation.column(0);
 
     let (index, _) = col
         .iter()
@@ -1,3 +1,4 @@
+#![allow(dead_code)]
 use std::ops::{Add, Deref, DerefMut, Mul, Sub};
 
 #[derive(Clone)]
@@ -0,0 +1,334 @@
+#![allow(dead_code)]
+use super::error::{Error, Result};
+use super::types::NetworkParams;
+
+/// Update rule applied to the network after every back propagation.
+/// Implementations keep their own per-parameter state (velocities, moment estimates, ...).
+pub trait Optimizer {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32);
+
+    /// Kind and hyperparameters the optimizer was built with.
+    fn kind(&self) -> OptimizerKind;
+
+    /// Copy of the internal state, e.g. to checkpoint it.
+    fn state(&self) -> OptimizerState;
+
+    /// Restores a state returned by `state` of the same kind of optimizer.
+    fn load_state(&mut self, state: OptimizerState) -> Result<()>;
+}
+
+/// Internal state of an optimizer, see `Optimizer::state`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OptimizerState {
+    /// Steps taken so far, only Adam keeps count.
+    pub time_step: u64,
+    /// Per-parameter buffers with the shape of the network (velocity, moment estimates, ...),
+    /// a buffer is empty until the first step.
+    pub buffers: Vec<NetworkParams>,
+}
+
+/// Optimizer selection and hyperparameters, see `OPTIMIZER` in `.env_sample`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OptimizerKind {
+    Sgd,
+    Momentum {
+        momentum: f32,
+        nesterov: bool,
+    },
+    RmsProp {
+        decay: f32,
+        epsilon: f32,
+    },
+    Adagrad {
+        epsilon: f32,
+    },
+    Adam {
+        beta_1: f32,
+        beta_2: f32,
+        epsilon: f32,
+    },
+}
+
+impl OptimizerKind {
+    pub fn build(&self) -> Box<dyn Optimizer> {
+        match *self {
+            OptimizerKind::Sgd => Box::new(Sgd),
+            OptimizerKind::Momentum { momentum, nesterov } => Box::new(Momentum {
+                momentum,
+                nesterov,
+                velocity: Vec::new(),
+            }),
+            OptimizerKind::RmsProp { decay, epsilon } => Box::new(RmsProp {
+                decay,
+                epsilon,
+                mean_square: Vec::new(),
+            }),
+            OptimizerKind::Adagrad { epsilon } => Box::new(Adagrad {
+                epsilon,
+                square_sum: Vec::new(),
+            }),
+            OptimizerKind::Adam {
+                beta_1,
+                beta_2,
+                epsilon,
+            } => Box::new(Adam {
+                beta_1,
+                beta_2,
+                epsilon,
+                time_step: 0,
+                first_moment: Vec::new(),
+                second_moment: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Iterates every weight and bias of the network in a fixed order.
+pub fn parameters(network_params: &NetworkParams) -> impl Iterator<Item = &f32> {
+    network_params.iter().flat_map(|layer| {
+        layer
+            .weights
+            .as_slice()
+            .iter()
+            .chain(layer.biases.as_slice())
+    })
+}
+
+/// Mutable counterpart of `parameters`, visiting the values in the same order.
+pub fn parameters_mut(network_params: &mut NetworkParams) -> impl Iterator<Item = &mut f32> {
+    network_params.iter_mut().flat_map(|layer| {
+        layer
+            .weights
+            .as_mut_slice()
+            .iter_mut()
+            .chain(layer.biases.as_mut_slice())
+    })
+}
+
+/// Buffers of a restored state, `count` is the number the optimizer keeps.
+fn take_buffers(state: OptimizerState, count: usize) -> Result<Vec<NetworkParams>> {
+    if state.buffers.len() != count {
+        return Err(Error::InvalidInput(format!(
+            "expected {count} optimizer buffers, got {}",
+            state.buffers.len()
+        )));
+    }
+
+    Ok(state.buffers)
+}
+
+/// Optimizer state with the shape of the network, lazily created on the first step.
+fn init_state(state: &mut NetworkParams, network_params: &NetworkParams) {
+    if state.is_empty() {
+        *state = network_params.clone();
+        parameters_mut(state).for_each(|value| *value = 0.0);
+    }
+}
+
+/// Plain stochastic gradient descent: w = w - alpha * dw
+pub struct Sgd;
+
+impl Optimizer for Sgd {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32) {
+        for (param, gradient) in parameters_mut(network_params).zip(parameters(gradients)) {
+            *param -= alpha * gradient;
+        }
+    }
+
+    fn kind(&self) -> OptimizerKind {
+        OptimizerKind::Sgd
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState::default()
+    }
+
+    fn load_state(&mut self, state: OptimizerState) -> Result<()> {
+        take_buffers(state, 0).map(|_| ())
+    }
+}
+
+/// SGD with (optionally Nesterov) momentum.
+/// v = momentum * v + dw, w = w - alpha * v  (w = w - alpha * (dw + momentum * v) for Nesterov)
+pub struct Momentum {
+    momentum: f32,
+    nesterov: bool,
+    velocity: NetworkParams,
+}
+
+impl Optimizer for Momentum {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32) {
+        init_state(&mut self.velocity, network_params);
+
+        for ((param, gradient), velocity) in parameters_mut(network_params)
+            .zip(parameters(gradients))
+            .zip(parameters_mut(&mut self.velocity))
+        {
+            *velocity = self.momentum * *velocity + gradient;
+
+            let update = if self.nesterov {
+                gradient + self.momentum * *velocity
+            } else {
+                *velocity
+            };
+
+            *param -= alpha * update;
+        }
+    }
+
+    fn kind(&self) -> OptimizerKind {
+        OptimizerKind::Momentum {
+            momentum: self.momentum,
+            nesterov: self.nesterov,
+        }
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            time_step: 0,
+            buffers: vec![self.velocity.clone()],
+        }
+    }
+
+    fn load_state(&mut self, state: OptimizerState) -> Result<()> {
+        [self.velocity] = take_buffers(state, 1)?.try_into().unwrap();
+        Ok(())
+    }
+}
+
+/// s = decay * s + (1 - decay) * dw^2, w = w - alpha * dw / (sqrt(s) + epsilon)
+pub struct RmsProp {
+    decay: f32,
+    epsilon: f32,
+    mean_square: NetworkParams,
+}
+
+impl Optimizer for RmsProp {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32) {
+        init_state(&mut self.mean_square, network_params);
+
+        for ((param, gradient), mean_square) in parameters_mut(network_params)
+            .zip(parameters(gradients))
+            .zip(parameters_mut(&mut self.mean_square))
+        {
+            *mean_square = self.decay * *mean_square + (1.0 - self.decay) * gradient * gradient;
+            *param -= alpha * gradient / (mean_square.sqrt() + self.epsilon);
+        }
+    }
+
+    fn kind(&self) -> OptimizerKind {
+        OptimizerKind::RmsProp {
+            decay: self.decay,
+            epsilon: self.epsilon,
+        }
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            time_step: 0,
+            buffers: vec![self.mean_square.clone()],
+        }
+    }
+
+    fn load_state(&mut self, state: OptimizerState) -> Result<()> {
+        [self.mean_square] = take_buffers(state, 1)?.try_into().unwrap();
+        Ok(())
+    }
+}
+
+/// s = s + dw^2, w = w - alpha * dw / (sqrt(s) + epsilon)
+pub struct Adagrad {
+    epsilon: f32,
+    square_sum: NetworkParams,
+}
+
+impl Optimizer for Adagrad {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32) {
+        init_state(&mut self.square_sum, network_params);
+
+        for ((param, gradient), square_sum) in parameters_mut(network_params)
+            .zip(parameters(gradients))
+            .zip(parameters_mut(&mut self.square_sum))
+        {
+            *square_sum += gradient * gradient;
+            *param -= alpha * gradient / (square_sum.sqrt() + self.epsilon);
+        }
+    }
+
+    fn kind(&self) -> OptimizerKind {
+        OptimizerKind::Adagrad {
+            epsilon: self.epsilon,
+        }
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            time_step: 0,
+            buffers: vec![self.square_sum.clone()],
+        }
+    }
+
+    fn load_state(&mut self, state: OptimizerState) -> Result<()> {
+        [self.square_sum] = take_buffers(state, 1)?.try_into().unwrap();
+        Ok(())
+    }
+}
+
+/// Adam with bias corrected first and second moment estimates.
+pub struct Adam {
+    beta_1: f32,
+    beta_2: f32,
+    epsilon: f32,
+    time_step: u64,
+    first_moment: NetworkParams,
+    second_moment: NetworkParams,
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, network_params: &mut NetworkParams, gradients: &NetworkParams, alpha: f32) {
+        init_state(&mut self.first_moment, network_params);
+        init_state(&mut self.second_moment, network_params);
+
+        self.time_step += 1;
+        // the corrections reach 1 long before i32::MAX steps
+        let time_step = self.time_step.min(i32::MAX as u64) as i32;
+        let first_correction = 1.0 - self.beta_1.powi(time_step);
+        let second_correction = 1.0 - self.beta_2.powi(time_step);
+
+        for (((param, gradient), first_moment), second_moment) in parameters_mut(network_params)
+            .zip(parameters(gradients))
+            .zip(parameters_mut(&mut self.first_moment))
+            .zip(parameters_mut(&mut self.second_moment))
+        {
+            *first_moment = self.beta_1 * *first_moment + (1.0 - self.beta_1) * gradient;
+            *second_moment =
+                self.beta_2 * *second_moment + (1.0 - self.beta_2) * gradient * gradient;
+
+            let first_estimate = *first_moment / first_correction;
+            let second_estimate = *second_moment / second_correction;
+
+            *param -= alpha * first_estimate / (second_estimate.sqrt() + self.epsilon);
+        }
+    }
+
+    fn kind(&self) -> OptimizerKind {
+        OptimizerKind::Adam {
+            beta_1: self.beta_1,
+            beta_2: self.beta_2,
+            epsilon: self.epsilon,
+        }
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            time_step: self.time_step,
+            buffers: vec![self.first_moment.clone(), self.second_moment.clone()],
+        }
+    }
+
+    fn load_state(&mut self, state: OptimizerState) -> Result<()> {
+        self.time_step = state.time_step;
+        [self.first_moment, self.second_moment] = take_buffers(state, 2)?.try_into().unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+#![allow(dead_code)]
+use super::dense_matrix::Matrix;
+use super::types::{Layer, NetworkParams};
+
+/// Regularisation of a single dense layer, see `L1`, `L2`, `WEIGHT_DECAY` and `MAX_NORM` in
+/// `.env_sample`. The default regularises nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Regularization {
+    /// Penalty l1 * sum(|w|) added to the loss and its gradient to the weight gradients.
+    pub l1: f32,
+    /// Coupled weight decay, the penalty l2 / 2 * sum(w^2) added to the loss and l2 * w to the
+    /// weight gradients, so adaptive optimizers rescale it like the gradients.
+    pub l2: f32,
+    /// Decoupled (AdamW style) weight decay, w = w - alpha * weight_decay * w after the
+    /// optimizer step. It isn't part of the loss.
+    pub weight_decay: f32,
+    /// Upper bound of the L2 norm of the incoming weights of every unit, enforced after every
+    /// update by scaling the weights of the unit down. Never applied to biases.
+    pub max_norm: Option<f32>,
+}
+
+/// Parameters of a layer that are regularised, the biases only when `include_biases` is set.
+fn regularized(layer: &Layer, include_biases: bool) -> impl Iterator<Item = &f32> {
+    let biases: &[f32] = match include_biases {
+        true => layer.biases.as_slice(),
+        false => &[],
+    };
+
+    layer.weights.as_slice().iter().chain(biases)
+}
+
+fn regularized_mut(layer: &mut Layer, include_biases: bool) -> impl Iterator<Item = &mut f32> {
+    let biases: &mut [f32] = match include_biases {
+        true => layer.biases.as_mut_slice(),
+        false => &mut [],
+    };
+
+    layer.weights.as_mut_slice().iter_mut().chain(biases)
+}
+
+/// Sum of the L1 and L2 penalties of every layer, added to the loss.
+pub fn penalty(
+    network_params: &NetworkParams,
+    regularization: &[Regularization],
+    include_biases: bool,
+) -> f32 {
+    network_params
+        .iter()
+        .zip(regularization)
+        .filter(|(_, reg)| reg.l1 != 0.0 || reg.l2 != 0.0)
+        .map(|(layer, reg)| {
+            regularized(layer, include_biases)
+                .map(|w| reg.l1 * w.abs() + reg.l2 / 2.0 * w * w)
+                .sum::<f32>()
+        })
+        .sum()
+}
+
+/// Adds the gradients of the L1 and L2 penalties to the gradients of back propagation.
+pub fn add_penalty_gradients(
+    network_params: &NetworkParams,
+    gradients: &mut NetworkParams,
+    regularization: &[Regularization],
+    include_biases: bool,
+) {
+    for ((layer, gradient), reg) in network_params
+        .iter()
+        .zip(gradients.iter_mut())
+        .zip(regularization)
+        .filter(|(_, reg)| reg.l1 != 0.0 || reg.l2 != 0.0)
+    {
+        for (dw, w) in
+            regularized_mut(gradient, include_biases).zip(regularized(layer, include_biases))
+        {
+            // the L1 subgradient is 0 at w = 0
+            let sign = if *w == 0.0 { 0.0 } else { w.signum() };
+            *dw += reg.l1 * sign + reg.l2 * w;
+        }
+    }
+}
+
+/// Applies the decoupled weight decay and the max-norm constraints after an optimizer step
+/// taken with the learning rate `alpha`.
+pub fn apply_constraints(
+    network_params: &mut NetworkParams,
+    regularization: &[Regularization],
+    include_biases: bool,
+    alpha: f32,
+) {
+    for (layer, reg) in network_params.iter_mut().zip(regularization) {
+        if reg.weight_decay != 0.0 {
+            let scale = 1.0 - alpha * reg.weight_decay;
+            regularized_mut(layer, include_biases).for_each(|w| *w *= scale);
+        }
+
+        if let Some(max_norm) = reg.max_norm {
+            clip_unit_norms(&mut layer.weights, max_norm);
+        }
+    }
+}
+
+/// Scales every row (the incoming weights of a unit) down to an L2 norm of at most `max_norm`.
+fn clip_unit_norms(weights: &mut Matrix, max_norm: f32) {
+    let cols = weights.cols();
+
+    for row in weights.as_mut_slice().chunks_mut(cols.max(1)) {
+        let norm = row.iter().map(|w| w * w).sum::<f32>().sqrt();
+
+        if norm > max_norm {
+            let scale = max_norm / norm;
+            row.iter_mut().for_each(|w| *w *= scale);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+#![allow(dead_code)]
+use std::f32::consts::PI;
+
+// a loss has to drop by this share of the best one to count as an improvement
+const PLATEAU_THRESHOLD: f32 = 1e-4;
+
+/// How the learning rate changes over a run, see `LR_SCHEDULE` in `.env_sample`.
+/// Every schedule starts after the optional linear warmup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    /// `ALPHA` for the whole run.
+    Constant,
+    /// alpha * gamma^(t / step_size)
+    Step { step_size: usize, gamma: f32 },
+    /// alpha * gamma^t
+    Exponential { gamma: f32 },
+    /// Cosine annealing from alpha down to min_alpha over `period` iterations, then restarting
+    /// with a period `period_mult` times longer (SGDR).
+    Cosine {
+        period: usize,
+        period_mult: usize,
+        min_alpha: f32,
+    },
+    /// Multiplies the learning rate by `factor` when the validation loss (the training loss
+    /// without a validation set) didn't improve for `patience` iterations.
+    Plateau {
+        factor: f32,
+        patience: usize,
+        min_alpha: f32,
+    },
+}
+
+impl Schedule {
+    /// Learning rate of the 0 based `iteration`, `alpha` is the base learning rate.
+    /// The first `warmup` iterations ramp up linearly to it.
+    pub fn alpha(
+        &self,
+        alpha: f32,
+        warmup: usize,
+        iteration: usize,
+        plateau: &PlateauState,
+    ) -> f32 {
+        if iteration < warmup {
+            return alpha * (iteration + 1) as f32 / warmup as f32;
+        }
+
+        let t = iteration - warmup;
+
+        match *self {
+            Schedule::Constant => alpha,
+            Schedule::Step { step_size, gamma } => alpha * gamma.powi((t / step_size) as i32),
+            Schedule::Exponential { gamma } => alpha * gamma.powi(t as i32),
+            Schedule::Cosine {
+                period,
+                period_mult,
+                min_alpha,
+            } => {
+                let (mut t, mut period) = (t, period);
+                while t >= period {
+                    t -= period;
+                    period *= period_mult;
+                }
+
+                let progress = t as f32 / period as f32;
+                min_alpha + (alpha - min_alpha) * (1.0 + (PI * progress).cos()) / 2.0
+            }
+            Schedule::Plateau { min_alpha, .. } => (alpha * plateau.scale).max(min_alpha),
+        }
+    }
+}
+
+/// Progress of `Schedule::Plateau`, the other schedules only depend on the iteration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlateauState {
+    /// Product of the reductions so far.
+    pub scale: f32,
+    pub best_loss: f32,
+    /// Iterations since the best loss.
+    pub wait: usize,
+}
+
+impl Default for PlateauState {
+    fn default() -> Self {
+        PlateauState {
+            scale: 1.0,
+            best_loss: f32::INFINITY,
+            wait: 0,
+        }
+    }
+}
+
+impl PlateauState {
+    /// Records the loss of a finished iteration, reducing the learning rate after `patience`
+    /// iterations without improvement. A patience of 0 reduces it at every such iteration.
+    pub fn update(&mut self, schedule: &Schedule, loss: f32) {
+        let Schedule::Plateau {
+            factor, patience, ..
+        } = *schedule
+        else {
+            return;
+        };
+
+        if loss < self.best_loss * (1.0 - PLATEAU_THRESHOLD) {
+            self.best_loss = loss;
+            self.wait = 0;
+        } else {
+            self.wait += 1;
+
+            if self.wait >= patience.max(1) {
+                self.scale *= factor;
+                self.wait = 0;
+            }
+        }
+    }
+}
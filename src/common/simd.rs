@@ -0,0 +1,171 @@
+#![allow(dead_code)]
+//! Vectorised element-wise kernels working in place on slices.
+//! On x86_64 the 8 lane AVX version is picked at runtime when the cpu supports it, otherwise
+//! the 4 lane SSE version (always available on x86_64) is used. Other targets and the tail
+//! that doesn't fill a whole register use the scalar version, which computes exactly the same
+//! IEEE result so every kernel matches its scalar loop bit for bit.
+
+/// target[i] = f(target[i], other[i])
+macro_rules! binary_kernel {
+    ($(#[$meta:meta])* $name:ident, |$x:ident, $y:ident| $avx:expr, $sse:expr, $scalar:expr) => {
+        $(#[$meta])*
+        pub fn $name(target: &mut [f32], other: &[f32]) {
+            if target.len() != other.len() {
+                panic!(
+                    "Slice lengths must match: {} and {}!",
+                    target.len(),
+                    other.len()
+                );
+            }
+
+            #[cfg(target_arch = "x86_64")]
+            let done = {
+                use std::arch::x86_64::*;
+
+                #[target_feature(enable = "avx")]
+                unsafe fn avx(target: &mut [f32], other: &[f32]) -> usize {
+                    let whole = target.len() / 8 * 8;
+                    for i in (0..whole).step_by(8) {
+                        let $x = _mm256_loadu_ps(target.as_ptr().add(i));
+                        let $y = _mm256_loadu_ps(other.as_ptr().add(i));
+                        _mm256_storeu_ps(target.as_mut_ptr().add(i), $avx);
+                    }
+                    whole
+                }
+
+                unsafe fn sse(target: &mut [f32], other: &[f32]) -> usize {
+                    let whole = target.len() / 4 * 4;
+                    for i in (0..whole).step_by(4) {
+                        let $x = _mm_loadu_ps(target.as_ptr().add(i));
+                        let $y = _mm_loadu_ps(other.as_ptr().add(i));
+                        _mm_storeu_ps(target.as_mut_ptr().add(i), $sse);
+                    }
+                    whole
+                }
+
+                // SAFETY: the lengths match and avx support is checked before use
+                unsafe {
+                    if is_x86_feature_detected!("avx") {
+                        avx(target, other)
+                    } else {
+                        sse(target, other)
+                    }
+                }
+            };
+            #[cfg(not(target_arch = "x86_64"))]
+            let done = 0;
+
+            for (cell, &$y) in target[done..].iter_mut().zip(&other[done..]) {
+                let $x = *cell;
+                *cell = $scalar;
+            }
+        }
+    };
+}
+
+/// target[i] = f(target[i], value)
+macro_rules! scalar_kernel {
+    ($(#[$meta:meta])* $name:ident, |$x:ident, $y:ident| $avx:expr, $sse:expr, $scalar:expr) => {
+        $(#[$meta])*
+        pub fn $name(target: &mut [f32], value: f32) {
+            #[cfg(target_arch = "x86_64")]
+            let done = {
+                use std::arch::x86_64::*;
+
+                #[target_feature(enable = "avx")]
+                unsafe fn avx(target: &mut [f32], value: f32) -> usize {
+                    let whole = target.len() / 8 * 8;
+                    let $y = _mm256_set1_ps(value);
+                    for i in (0..whole).step_by(8) {
+                        let $x = _mm256_loadu_ps(target.as_ptr().add(i));
+                        _mm256_storeu_ps(target.as_mut_ptr().add(i), $avx);
+                    }
+                    whole
+                }
+
+                unsafe fn sse(target: &mut [f32], value: f32) -> usize {
+                    let whole = target.len() / 4 * 4;
+                    let $y = _mm_set1_ps(value);
+                    for i in (0..whole).step_by(4) {
+                        let $x = _mm_loadu_ps(target.as_ptr().add(i));
+                        _mm_storeu_ps(target.as_mut_ptr().add(i), $sse);
+                    }
+                    whole
+                }
+
+                // SAFETY: avx support is checked before use
+                unsafe {
+                    if is_x86_feature_detected!("avx") {
+                        avx(target, value)
+                    } else {
+                        sse(target, value)
+                    }
+                }
+            };
+            #[cfg(not(target_arch = "x86_64"))]
+            let done = 0;
+
+            let $y = value;
+            for cell in target[done..].iter_mut() {
+                let $x = *cell;
+                *cell = $scalar;
+            }
+        }
+    };
+}
+
+binary_kernel!(add, |x, y| _mm256_add_ps(x, y), _mm_add_ps(x, y), x + y);
+binary_kernel!(
+    subtract,
+    |x, y| _mm256_sub_ps(x, y),
+    _mm_sub_ps(x, y),
+    x - y
+);
+binary_kernel!(
+    /// Element-wise (hadamard) product.
+    multiply,
+    |x, y| _mm256_mul_ps(x, y),
+    _mm_mul_ps(x, y),
+    x * y
+);
+binary_kernel!(divide, |x, y| _mm256_div_ps(x, y), _mm_div_ps(x, y), x / y);
+binary_kernel!(
+    /// Keeps the larger value, `target` wins ties and NaNs in `other`.
+    max,
+    // max_ps returns its second operand unless the first one is greater
+    |x, y| _mm256_max_ps(y, x),
+    _mm_max_ps(y, x),
+    if y > x { y } else { x }
+);
+
+scalar_kernel!(
+    add_scalar,
+    |x, y| _mm256_add_ps(x, y),
+    _mm_add_ps(x, y),
+    x + y
+);
+scalar_kernel!(
+    subtract_scalar,
+    |x, y| _mm256_sub_ps(x, y),
+    _mm_sub_ps(x, y),
+    x - y
+);
+scalar_kernel!(scale, |x, y| _mm256_mul_ps(x, y), _mm_mul_ps(x, y), x * y);
+scalar_kernel!(
+    divide_scalar,
+    |x, y| _mm256_div_ps(x, y),
+    _mm_div_ps(x, y),
+    x / y
+);
+scalar_kernel!(
+    /// Replaces everything that isn't greater than `value` (including NaN) with `value`.
+    max_scalar,
+    |x, y| _mm256_max_ps(x, y),
+    _mm_max_ps(x, y),
+    if x > y { x } else { y }
+);
+
+/// max(x, 0) with NaN mapped to 0.
+pub fn relu(target: &mut [f32]) {
+    max_scalar(target, 0.0);
+}
@@ -1 +1,44 @@
-pub type NetworkParams = (Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Vec<f32>>);
+use super::activation::Activation;
+use super::dense_matrix::Matrix;
+use std::borrow::Cow;
+
+/// Weights, biases and activation function of a single dense layer.
+/// weights: units x inputs  biases: units x 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer {
+    pub weights: Matrix,
+    pub biases: Matrix,
+    pub activation: Activation,
+}
+
+/// Values produced by a single layer during forward propagation.
+/// z: units x m  activation: units x m  dropout_mask: units x m
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerOutput {
+    pub z: Matrix,
+    pub activation: Matrix,
+    /// Inverted dropout mask of a hidden layer during training, 0 for dropped units and
+    /// 1 / (1 - rate) for kept ones. `None` during inference or without dropout.
+    pub dropout_mask: Option<Matrix>,
+}
+
+impl LayerOutput {
+    /// Activation passed on to the next layer, with the dropout mask applied.
+    pub fn output(&self) -> Cow<'_, Matrix> {
+        match &self.dropout_mask {
+            Some(mask) => Cow::Owned(self.activation.multiply(mask)),
+            None => Cow::Borrowed(&self.activation),
+        }
+    }
+}
+
+/// Dense layers ordered from the first hidden layer to the output layer.
+/// Gradients returned by back propagation use the same layout.
+pub type NetworkParams = Vec<Layer>;
+
+/// Per layer outputs of forward propagation, in the same order as `NetworkParams`.
+pub type ForwardCache = Vec<LayerOutput>;
+
+/// Labels and data of a dataset, one sample per column.
+/// labels: 1 x m  data: inputs x m
+pub type Dataset = (Matrix, Matrix);
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{activation::Activation, dense_matrix::Matrix};
+
+    const ELEMENT_WISE: [Activation; 8] = [
+        Activation::Relu,
+        Activation::LeakyRelu(0.1),
+        Activation::Elu(1.0),
+        Activation::Gelu,
+        Activation::Sigmoid,
+        Activation::Tanh,
+        Activation::Softplus,
+        Activation::Identity,
+    ];
+
+    fn assert_close(actual: &Matrix, expected: &[f32], tolerance: f32) {
+        for (a, e) in actual.as_slice().iter().zip(expected) {
+            assert!((a - e).abs() < tolerance, "expected {e}, got {a}");
+        }
+    }
+
+    fn row(values: &[f32]) -> Matrix {
+        Matrix::new(1, values.len(), values.to_vec())
+    }
+
+    #[test]
+    fn test_forward() {
+        let z = row(&[-2.0, 0.0, 3.0]);
+
+        assert_close(
+            &Activation::LeakyRelu(0.1).forward(&z),
+            &[-0.2, 0.0, 3.0],
+            1e-6,
+        );
+        assert_close(
+            &Activation::Elu(1.0).forward(&z),
+            &[-0.864_664_7, 0.0, 3.0],
+            1e-6,
+        );
+        assert_close(
+            &Activation::Sigmoid.forward(&z),
+            &[0.119_202_92, 0.5, 0.952_574_13],
+            1e-6,
+        );
+        assert_close(
+            &Activation::Softplus.forward(&z),
+            &[0.126_928_01, std::f32::consts::LN_2, 3.048_587_4],
+            1e-6,
+        );
+        assert_close(
+            &Activation::Gelu.forward(&z),
+            &[-0.045_402_3, 0.0, 2.996_362_6],
+            1e-5,
+        );
+        assert_eq!(Activation::Identity.forward(&z), z);
+    }
+
+    #[test]
+    fn test_derivative_matches_finite_difference() {
+        // avoid 0.0 where relu like functions are not differentiable
+        let z = row(&[-2.5, -0.7, 0.3, 1.9]);
+        let step = 1e-3;
+        let z_plus = z.map(|x| x + step);
+        let z_minus = z.map(|x| x - step);
+
+        for activation in ELEMENT_WISE {
+            let numeric = activation
+                .forward(&z_plus)
+                .subtract(&activation.forward(&z_minus))
+                .divide(2.0 * step);
+
+            assert_close(&activation.derivative(&z), numeric.as_slice(), 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_softmax_backward() {
+        let z = Matrix::from(vec![vec![1.0, -1.0], vec![2.0, 0.5], vec![0.5, 0.0]]);
+        let gradient = Matrix::from(vec![vec![0.3, -1.0], vec![-0.2, 0.4], vec![1.0, 0.1]]);
+        let activation = Activation::Softmax.forward(&z);
+
+        let result = Activation::Softmax.backward(&z, &activation, &gradient);
+
+        // every column of a softmax gradient sums to zero
+        assert!(result.col_sum().iter().all(|sum| sum.abs() < 1e-6));
+
+        // d(sum(a * g)) / dz_00 by finite difference
+        let step = 1e-3;
+        let objective = |z: &Matrix| -> f32 {
+            Activation::Softmax
+                .forward(z)
+                .multiply(&gradient)
+                .column(0)
+                .iter()
+                .sum()
+        };
+        let mut z_plus = z.clone();
+        let mut z_minus = z.clone();
+        z_plus[(0, 0)] += step;
+        z_minus[(0, 0)] -= step;
+
+        let numeric = (objective(&z_plus) - objective(&z_minus)) / (2.0 * step);
+        assert!((result[(0, 0)] - numeric).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("relu".parse::<Activation>(), Ok(Activation::Relu));
+        assert_eq!(
+            "leaky_relu:0.2".parse::<Activation>(),
+            Ok(Activation::LeakyRelu(0.2))
+        );
+        assert_eq!("ELU".parse::<Activation>(), Ok(Activation::Elu(1.0)));
+        assert!("swish".parse::<Activation>().is_err());
+
+        for activation in ELEMENT_WISE {
+            assert_eq!(activation.to_string().parse::<Activation>(), Ok(activation));
+        }
+    }
+}
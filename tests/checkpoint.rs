@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::{default_activations, Activation},
+        config::TrainingConfig,
+        dense_matrix::Matrix,
+        early_stopping::EarlyStoppingState,
+        error::Error,
+        io::{load_checkpoint, remove_checkpoint, save_checkpoint, CHECKPOINT_FILE},
+        matrix::create_rng,
+        model_file::{decode_checkpoint, encode_checkpoint},
+        network_functions::{continue_training, train, TrainingState},
+        optimizer::OptimizerKind,
+        schedule::PlateauState,
+    };
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn get_model_dir(name: &str) -> PathBuf {
+        let model_dir = env::temp_dir().join(format!(
+            "rustnet_checkpoint_test_{}_{name}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&model_dir);
+        model_dir
+    }
+
+    fn get_data() -> (Matrix, Matrix) {
+        let labels = Matrix::from_fn(1, 30, |_, j| (j % 3) as f32);
+        let data = Matrix::from_fn(4, 30, |i, j| ((i + j * 7) % 5) as f32 / 4.0);
+        (labels, data)
+    }
+
+    fn get_config() -> TrainingConfig {
+        TrainingConfig {
+            layer_sizes: vec![4, 6, 3],
+            activations: default_activations(2),
+            iterations: 6,
+            alpha: 0.05,
+            batch_size: Some(7),
+            optimizer: OptimizerKind::Adam {
+                beta_1: 0.9,
+                beta_2: 0.999,
+                epsilon: 1e-8,
+            },
+            ..TrainingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (labels, data) = get_data();
+        let config = TrainingConfig {
+            iterations: 2,
+            ..get_config()
+        };
+        let mut state = TrainingState::new(&config, &mut create_rng(Some(1)));
+        continue_training(&mut state, &labels, &data, None, &config, |_| Ok(())).unwrap();
+
+        state.plateau = PlateauState {
+            scale: 0.25,
+            best_loss: 0.5,
+            wait: 3,
+        };
+
+        state.early_stopping = EarlyStoppingState {
+            best: Some(0.75),
+            best_iteration: 1,
+            wait: 1,
+            best_params: state.network_params.clone(),
+        };
+
+        let checkpoint = state.checkpoint(1);
+        assert_eq!(checkpoint.iteration, 2);
+        assert_eq!(checkpoint.optimizer_state.time_step, 10);
+        assert_eq!(checkpoint.optimizer_state.buffers.len(), 2);
+
+        let mut bytes = encode_checkpoint(&checkpoint);
+        assert_eq!(decode_checkpoint(&bytes).unwrap(), checkpoint);
+
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        assert!(matches!(
+            decode_checkpoint(&bytes),
+            Err(Error::CorruptModel(_))
+        ));
+    }
+
+    #[test]
+    fn test_resume_matches_uninterrupted_run() {
+        let (labels, data) = get_data();
+        let config = get_config();
+        let model_dir = get_model_dir("resume");
+
+        let uninterrupted = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(3)),
+        );
+
+        // checkpoint every 2 iterations and stop after the one written at iteration 4
+        let mut state = TrainingState::new(&config, &mut create_rng(Some(3)));
+        let interrupted = continue_training(&mut state, &labels, &data, None, &config, |state| {
+            if state.iteration % 2 == 0 {
+                save_checkpoint(&state.checkpoint(3), &model_dir)?;
+            }
+            match state.iteration {
+                4 => Err(Error::InvalidInput("interrupted".to_owned())),
+                _ => Ok(()),
+            }
+        });
+        assert!(interrupted.is_err());
+
+        let checkpoint = load_checkpoint(&model_dir).unwrap();
+        assert_eq!((checkpoint.seed, checkpoint.iteration), (3, 4));
+
+        let mut resumed = TrainingState::resume(checkpoint, &config).unwrap();
+        continue_training(&mut resumed, &labels, &data, None, &config, |_| Ok(())).unwrap();
+
+        assert_eq!(resumed.iteration, config.iterations);
+        assert_eq!(resumed.network_params, uninterrupted);
+
+        remove_checkpoint(&model_dir).unwrap();
+        assert!(!model_dir.join(CHECKPOINT_FILE).exists());
+        remove_checkpoint(&model_dir).unwrap();
+        assert!(matches!(
+            load_checkpoint(&model_dir),
+            Err(Error::InvalidInput(_))
+        ));
+
+        fs::remove_dir_all(model_dir).unwrap();
+    }
+
+    #[test]
+    fn test_resume_with_other_network() {
+        let config = get_config();
+        let checkpoint = TrainingState::new(&config, &mut create_rng(Some(0))).checkpoint(0);
+
+        let wider = TrainingConfig {
+            layer_sizes: vec![4, 8, 3],
+            ..get_config()
+        };
+        let other_activations = TrainingConfig {
+            activations: vec![Activation::Tanh, Activation::Softmax],
+            ..get_config()
+        };
+        let other_optimizer = TrainingConfig {
+            optimizer: OptimizerKind::Sgd,
+            ..get_config()
+        };
+        let other_beta = TrainingConfig {
+            optimizer: OptimizerKind::Adam {
+                beta_1: 0.8,
+                beta_2: 0.999,
+                epsilon: 1e-8,
+            },
+            ..get_config()
+        };
+
+        assert!(matches!(
+            TrainingState::resume(checkpoint.clone(), &wider),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            TrainingState::resume(checkpoint.clone(), &other_activations),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            TrainingState::resume(checkpoint.clone(), &other_optimizer),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            TrainingState::resume(checkpoint, &other_beta),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        // momentum and rmsprop both keep one buffer, the velocity must not become a mean square
+        let (labels, data) = get_data();
+        let momentum = TrainingConfig {
+            optimizer: OptimizerKind::Momentum {
+                momentum: 0.9,
+                nesterov: false,
+            },
+            iterations: 1,
+            ..get_config()
+        };
+        let mut state = TrainingState::new(&momentum, &mut create_rng(Some(0)));
+        continue_training(&mut state, &labels, &data, None, &momentum, |_| Ok(())).unwrap();
+        let checkpoint = decode_checkpoint(&encode_checkpoint(&state.checkpoint(0))).unwrap();
+
+        let rms_prop = TrainingConfig {
+            optimizer: OptimizerKind::RmsProp {
+                decay: 0.9,
+                epsilon: 1e-8,
+            },
+            ..momentum.clone()
+        };
+        assert!(matches!(
+            TrainingState::resume(checkpoint.clone(), &rms_prop),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(TrainingState::resume(checkpoint, &momentum).is_ok());
+    }
+}
@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+    use rustnet::cli::{describe_network, Cli, Command};
+    use rustnet::common::{
+        activation::{default_activations, Activation},
+        config::TrainingConfig,
+        early_stopping::{EarlyStopping, Monitor},
+        error::Error,
+        initializer::default_initializers,
+        loss::Loss,
+        matrix::{create_network_params, create_rng},
+        optimizer::OptimizerKind,
+        regularization::Regularization,
+        schedule::Schedule,
+    };
+    use std::path::PathBuf;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(args).unwrap().command.unwrap()
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_train_flags_override_config() {
+        let Command::Train(args) = parse(&[
+            "rustnet",
+            "train",
+            "--input",
+            "train.csv",
+            "--force",
+            "--iterations",
+            "5",
+            "--alpha",
+            "0.01",
+            "--schedule",
+            "exponential",
+            "--warmup",
+            "3",
+            "--activations",
+            "tanh,softmax",
+            "--batch-size",
+            "0",
+            "--optimizer",
+            "sgd",
+            "--loss",
+            "mse",
+            "--validation-split",
+            "0.2",
+            "--early-stopping",
+            "val_accuracy",
+            "--patience",
+            "4",
+            "--seed",
+            "42",
+            "--checkpoint-every",
+            "10",
+            "--resume",
+        ]) else {
+            panic!("expected the train command");
+        };
+
+        let mut config = TrainingConfig {
+            batch_size: Some(32),
+            optimizer: OptimizerKind::Adagrad { epsilon: 1e-8 },
+            ..TrainingConfig::default()
+        };
+        args.apply(&mut config).unwrap();
+
+        assert_eq!(args.input.input.as_deref(), Some("train.csv"));
+        assert!(args.force);
+        assert!(args.resume);
+        assert_eq!(
+            config,
+            TrainingConfig {
+                iterations: 5,
+                alpha: 0.01,
+                schedule: Schedule::Exponential { gamma: 0.95 },
+                warmup: 3,
+                activations: vec![Activation::Tanh, Activation::Softmax],
+                batch_size: None,
+                optimizer: OptimizerKind::Sgd,
+                loss: Loss::MeanSquaredError,
+                validation_split: 0.2,
+                early_stopping: Some(EarlyStopping {
+                    monitor: Monitor::Accuracy,
+                    patience: 4,
+                    min_delta: 0.0,
+                }),
+                seed: Some(42),
+                checkpoint_every: Some(10),
+                ..TrainingConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_regularization_flags() {
+        let Command::Train(args) = parse(&[
+            "rustnet",
+            "train",
+            "--layers",
+            "784,64,32,10",
+            "--l2",
+            "0.01",
+            "--max-norm",
+            "3,3,0",
+            "--regularize-biases",
+            "--dropout",
+            "0.5,0.2",
+        ]) else {
+            panic!("expected the train command");
+        };
+
+        let mut config = TrainingConfig::default();
+        args.apply(&mut config).unwrap();
+
+        let clipped = Regularization {
+            l2: 0.01,
+            max_norm: Some(3.0),
+            ..Regularization::default()
+        };
+        let unclipped = Regularization {
+            l2: 0.01,
+            ..Regularization::default()
+        };
+        assert_eq!(config.regularization, vec![clipped, clipped, unclipped]);
+        assert!(config.regularize_biases);
+        assert_eq!(config.dropout, vec![0.5, 0.2]);
+
+        let Command::Train(args) = parse(&["rustnet", "train", "--l1", "0.1,0.2,0.3"]) else {
+            panic!("expected the train command");
+        };
+        assert!(args.apply(&mut TrainingConfig::default()).is_err());
+
+        let Command::Train(args) = parse(&["rustnet", "train", "--max-norm=-1"]) else {
+            panic!("expected the train command");
+        };
+        assert!(matches!(
+            args.apply(&mut TrainingConfig::default()),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_layers_reset_mismatched_activations() {
+        let Command::Train(args) = parse(&["rustnet", "train", "--layers", "784,64,32,10"]) else {
+            panic!("expected the train command");
+        };
+
+        let mut config = TrainingConfig::default();
+        args.apply(&mut config).unwrap();
+
+        assert_eq!(config.layer_sizes, vec![784, 64, 32, 10]);
+        assert_eq!(config.activations, default_activations(3));
+        assert_eq!(config.initializers, default_initializers(3));
+        assert_eq!(config.regularization, vec![Regularization::default(); 3]);
+        assert_eq!(config.dropout, vec![0.0; 2]);
+    }
+
+    #[test]
+    fn test_invalid_flags() {
+        assert!(Cli::try_parse_from(["rustnet", "train", "--loss", "kl"]).is_err());
+        assert!(Cli::try_parse_from(["rustnet", "train", "--activations", "relu,nope"]).is_err());
+        assert!(Cli::try_parse_from(["rustnet", "fit"]).is_err());
+
+        let Command::Train(args) = parse(&["rustnet", "train", "--patience", "3"]) else {
+            panic!("expected the train command");
+        };
+        assert!(args.apply(&mut TrainingConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_other_commands() {
+        assert!(Cli::try_parse_from(["rustnet"]).unwrap().command.is_none());
+        assert!(matches!(parse(&["rustnet", "draw"]), Command::Draw));
+        assert!(matches!(parse(&["rustnet", "inspect"]), Command::Inspect));
+
+        let Command::Predict(args) =
+            parse(&["rustnet", "predict", "-i", "in.csv", "-o", "out.txt"])
+        else {
+            panic!("expected the predict command");
+        };
+        assert_eq!(args.input.input.as_deref(), Some("in.csv"));
+        assert_eq!(args.output.as_deref(), Some("out.txt"));
+    }
+
+    #[test]
+    fn test_model_dir() {
+        let cli =
+            Cli::try_parse_from(["rustnet", "inspect", "--model-dir", "models/wide"]).unwrap();
+        assert_eq!(cli.model_dir, PathBuf::from("models/wide"));
+
+        let cli = Cli::try_parse_from(["rustnet", "-m", "models/deep", "draw"]).unwrap();
+        assert_eq!(cli.model_dir, PathBuf::from("models/deep"));
+    }
+
+    #[test]
+    fn test_describe_network() {
+        let network_params = create_network_params(
+            &[784, 10, 10],
+            &default_activations(2),
+            &default_initializers(2),
+            &mut create_rng(Some(0)),
+        );
+
+        assert_eq!(
+            describe_network(&network_params),
+            "Layer 1: 784 -> 10 (relu), 7850 parameters\n\
+             Layer 2: 10 -> 10 (softmax), 110 parameters\n\
+             Total parameters: 7960\n"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        dataset::{read_dataset, read_dataset_file},
+        error::Error,
+        matrix::create_rng,
+        network_functions::prepare_data,
+    };
+    use std::env;
+    use std::fs;
+
+    // every pixel of a sample is its label times 10, so shuffled columns can still be checked
+    const DATASET: &str = "3,30,30,30\n0,0,0,0\n7,70,70,70\n1,10,10,10\n";
+
+    fn assert_matching_samples(labels: &[f32], data: &[Vec<f32>]) {
+        for (j, label) in labels.iter().enumerate() {
+            for row in data {
+                assert_eq!(row[j], label * 10.0 / 255.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_prepare_data() {
+        let (labels, data) = read_dataset(DATASET.as_bytes(), 0, &mut create_rng(Some(0))).unwrap();
+        let (expected_labels, expected_data) = prepare_data(
+            vec![
+                vec![3.0, 30.0, 30.0, 30.0],
+                vec![0.0, 0.0, 0.0, 0.0],
+                vec![7.0, 70.0, 70.0, 70.0],
+                vec![1.0, 10.0, 10.0, 10.0],
+            ],
+            &mut create_rng(Some(0)),
+        );
+
+        assert_eq!(labels.shape(), expected_labels.shape());
+        assert_eq!(data.shape(), expected_data.shape());
+
+        let mut sorted_labels = labels.row(0).to_vec();
+        sorted_labels.sort_by(f32::total_cmp);
+        assert_eq!(sorted_labels, vec![0.0, 1.0, 3.0, 7.0]);
+
+        assert_matching_samples(labels.row(0), &data.to_nested());
+    }
+
+    #[test]
+    fn test_seeded_shuffle() {
+        let read = |seed| read_dataset(DATASET.as_bytes(), 0, &mut create_rng(Some(seed))).unwrap();
+
+        assert_eq!(read(1), read(1));
+        assert!((2..10).any(|seed| read(seed) != read(1)));
+    }
+
+    #[test]
+    fn test_header_is_skipped() {
+        let path = env::temp_dir().join(format!("rustnet_dataset_{}.csv", std::process::id()));
+        fs::write(&path, format!("label,1x1,1x2,1x3\n{DATASET}")).unwrap();
+
+        let (labels, data) = read_dataset_file(&path, &mut create_rng(Some(0))).unwrap();
+
+        assert_eq!(labels.shape(), (1, 4));
+        assert_eq!(data.shape(), (3, 4));
+        assert_matching_samples(labels.row(0), &data.to_nested());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_rows() {
+        let Err(Error::Parse { row, column, value }) = read_dataset(
+            "label,a,b\n1,2,3\n4,5,six\n".as_bytes(),
+            0,
+            &mut create_rng(Some(0)),
+        ) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((row, column, value.as_str()), (2, 2, "six"));
+
+        // a broken first sample isn't mistaken for a header
+        let Err(Error::Parse { row, column, value }) =
+            read_dataset("1,2,x\n4,5,6\n".as_bytes(), 0, &mut create_rng(Some(0)))
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((row, column, value.as_str()), (0, 2, "x"));
+
+        assert!(matches!(
+            read_dataset("1,2,3\n4,5\n".as_bytes(), 0, &mut create_rng(Some(0))),
+            Err(Error::Csv(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_dataset() {
+        let (labels, data) = read_dataset("".as_bytes(), 16, &mut create_rng(Some(0))).unwrap();
+
+        assert_eq!(labels.shape(), (1, 0));
+        assert_eq!(data.shape(), (0, 0));
+    }
+}
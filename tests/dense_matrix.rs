@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        dense_matrix::Matrix,
+        integration_test_vars::{get_image_test, get_w_1_test},
+        matrix::{dot_product, transpose},
+    };
+
+    fn get_matrix() -> Matrix {
+        Matrix::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]])
+    }
+
+    #[test]
+    fn test_nested_conversion() {
+        let nested = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let matrix = Matrix::from(nested.clone());
+
+        assert_eq!(matrix.shape(), (2, 3));
+        assert_eq!(matrix.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(Vec::<Vec<f32>>::from(&matrix), nested);
+    }
+
+    #[test]
+    #[should_panic(expected = "Every row must have the same number of columns")]
+    fn test_ragged_conversion() {
+        let _ = Matrix::from(vec![vec![1.0, 2.0], vec![3.0]]);
+    }
+
+    #[test]
+    fn test_indexing_and_slices() {
+        let mut matrix = get_matrix();
+
+        assert_eq!(matrix[(1, 2)], 6.0);
+        assert_eq!(matrix.row(1), &[4.0, 5.0, 6.0]);
+        assert_eq!(matrix.column(1), vec![2.0, 5.0]);
+
+        matrix[(0, 1)] = 9.0;
+        matrix.row_mut(1)[0] = 7.0;
+        assert_eq!(
+            matrix.to_nested(),
+            vec![vec![1.0, 9.0, 3.0], vec![7.0, 5.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn test_view() {
+        let matrix = Matrix::from(vec![
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ]);
+        let view = matrix.view(1..3, 1..3);
+
+        assert_eq!(view.shape(), (2, 2));
+        assert_eq!(view.row(1), &[8.0, 9.0]);
+        assert_eq!(view.get(0, 1), 6.0);
+        assert_eq!(
+            view.to_matrix(),
+            Matrix::from(vec![vec![5.0, 6.0], vec![8.0, 9.0]])
+        );
+        assert_eq!(matrix.as_view().to_matrix(), matrix);
+    }
+
+    #[test]
+    fn test_matches_nested_operations() {
+        let w_1 = Matrix::from(get_w_1_test());
+        let image = Matrix::from(get_image_test());
+
+        assert_eq!(
+            w_1.dot(&image).to_nested(),
+            dot_product(&get_w_1_test(), &get_image_test()).unwrap()
+        );
+        assert_eq!(w_1.transpose().to_nested(), transpose(&get_w_1_test()));
+    }
+
+    #[test]
+    fn test_transpose_in_place() {
+        for (rows, cols) in [(0, 3), (1, 5), (5, 1), (2, 3), (3, 3), (7, 13), (64, 785)] {
+            let mut matrix = Matrix::from_fn(rows, cols, |i, j| (i * cols + j) as f32);
+            let expected = matrix.transpose();
+
+            matrix.transpose_in_place();
+            assert_eq!(matrix, expected, "{rows}x{cols}");
+        }
+    }
+
+    #[test]
+    fn test_swap_rows() {
+        let mut matrix = Matrix::from(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+
+        matrix.swap_rows(2, 0);
+        matrix.swap_rows(1, 1);
+        assert_eq!(
+            matrix.to_nested(),
+            vec![vec![5.0, 6.0], vec![3.0, 4.0], vec![1.0, 2.0]]
+        );
+    }
+
+    #[test]
+    fn test_reductions() {
+        let matrix = get_matrix();
+        let bias = Matrix::from(vec![vec![1.0], vec![-1.0]]);
+
+        assert_eq!(matrix.row_sum(), Matrix::from(vec![vec![6.0], vec![15.0]]));
+        assert_eq!(matrix.col_sum(), vec![5.0, 7.0, 9.0]);
+        assert_eq!(matrix.col_max(), vec![4.0, 5.0, 6.0]);
+        assert_eq!(
+            matrix.add_column(&bias).to_nested(),
+            vec![vec![2.0, 3.0, 4.0], vec![3.0, 4.0, 5.0]]
+        );
+        assert_eq!(
+            matrix.select_columns(&[2, 0]).to_nested(),
+            vec![vec![3.0, 1.0], vec![6.0, 4.0]]
+        );
+        assert_eq!((matrix.min(), matrix.max(), matrix.mean()), (1.0, 6.0, 3.5));
+    }
+}
@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::{default_activations, Activation},
+        config::TrainingConfig,
+        dense_matrix::Matrix,
+        initializer::Initializer,
+        loss::Loss,
+        matrix::{create_network_params, create_rng},
+        network_functions::{
+            back_propagation, forward_propagation, forward_propagation_with_dropout, one_hot, train,
+        },
+        types::NetworkParams,
+    };
+
+    fn get_network_params(layer_sizes: &[usize], activations: &[Activation]) -> NetworkParams {
+        create_network_params(
+            layer_sizes,
+            activations,
+            &vec![Initializer::XavierNormal; activations.len()],
+            &mut create_rng(Some(0)),
+        )
+    }
+
+    fn get_input(rows: usize, cols: usize) -> Matrix {
+        Matrix::from_fn(rows, cols, |i, j| ((i * 7 + j * 3) % 10) as f32 / 10.0)
+    }
+
+    #[test]
+    fn test_inference_has_no_dropout() {
+        let network_params = get_network_params(&[4, 8, 3], &default_activations(2));
+        let input = get_input(4, 5);
+
+        let inference = forward_propagation(&network_params, &input);
+        assert!(inference.iter().all(|output| output.dropout_mask.is_none()));
+        assert_eq!(inference, forward_propagation(&network_params, &input));
+
+        // a rate of 0 draws no mask
+        let training = forward_propagation_with_dropout(
+            &network_params,
+            &input,
+            &[0.0],
+            &mut create_rng(None),
+        );
+        assert_eq!(training, inference);
+    }
+
+    #[test]
+    fn test_inverted_dropout_mask() {
+        let network_params = get_network_params(&[4, 200, 3], &default_activations(2));
+        let input = get_input(4, 100);
+
+        let forward_cache = forward_propagation_with_dropout(
+            &network_params,
+            &input,
+            &[0.3],
+            &mut create_rng(Some(1)),
+        );
+        let mask = forward_cache[0].dropout_mask.as_ref().unwrap();
+
+        assert_eq!(mask.shape(), (200, 100));
+        let dropped = mask
+            .as_slice()
+            .iter()
+            .filter(|value| **value == 0.0)
+            .count();
+        let share = dropped as f32 / mask.as_slice().len() as f32;
+        assert!((share - 0.3).abs() < 0.01, "{share}");
+        assert!(mask
+            .as_slice()
+            .iter()
+            .all(|value| *value == 0.0 || (value - 1.0 / 0.7).abs() < 1e-6));
+
+        // the expected output of a unit stays the one of inference
+        let activation = &forward_cache[0].activation;
+        let ratio = forward_cache[0].output().sum() / activation.sum();
+        assert!((ratio - 1.0).abs() < 0.02, "{ratio}");
+
+        // the output layer is never dropped
+        assert!(forward_cache[1].dropout_mask.is_none());
+    }
+
+    #[test]
+    fn test_seeded_masks() {
+        let network_params = get_network_params(&[4, 8, 8, 3], &default_activations(3));
+        let input = get_input(4, 5);
+        let masks = |seed| {
+            forward_propagation_with_dropout(
+                &network_params,
+                &input,
+                &[0.5, 0.2],
+                &mut create_rng(Some(seed)),
+            )
+            .into_iter()
+            .map(|output| output.dropout_mask)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(masks(3), masks(3));
+        assert_ne!(masks(3), masks(4));
+    }
+
+    #[test]
+    fn test_back_propagation_reuses_the_masks() {
+        let activations = [Activation::Tanh, Activation::Sigmoid, Activation::Softmax];
+        let network_params = get_network_params(&[3, 5, 4, 2], &activations);
+        let input = get_input(3, 6);
+        let labels = Matrix::from_fn(1, 6, |_, j| (j % 2) as f32);
+        let dropout = [0.5, 0.3];
+
+        // the masks only depend on the generator, every pass below drops the same units
+        let loss = |network_params: &NetworkParams| {
+            let forward_cache = forward_propagation_with_dropout(
+                network_params,
+                &input,
+                &dropout,
+                &mut create_rng(Some(5)),
+            );
+            let output = forward_cache.last().unwrap();
+            Loss::CrossEntropy.compute(Activation::Softmax, output, &one_hot(&labels, 2))
+        };
+
+        let forward_cache = forward_propagation_with_dropout(
+            &network_params,
+            &input,
+            &dropout,
+            &mut create_rng(Some(5)),
+        );
+        let gradients = back_propagation(
+            &network_params,
+            &forward_cache,
+            &labels,
+            &input,
+            Loss::CrossEntropy,
+        );
+
+        let epsilon = 1e-2;
+        for l in 0..network_params.len() {
+            for i in 0..network_params[l].weights.as_slice().len() {
+                let mut plus = network_params.clone();
+                plus[l].weights.as_mut_slice()[i] += epsilon;
+                let mut minus = network_params.clone();
+                minus[l].weights.as_mut_slice()[i] -= epsilon;
+
+                let numerical = (loss(&plus) - loss(&minus)) / (2.0 * epsilon);
+                let analytical = gradients[l].weights.as_slice()[i];
+                assert!(
+                    (numerical - analytical).abs() < 1e-3,
+                    "layer {l} weight {i}: {numerical} != {analytical}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_training_with_dropout() {
+        let labels = Matrix::from_fn(1, 20, |_, j| (j % 2) as f32);
+        let data = Matrix::from_fn(2, 20, |i, j| ((i + j) % 2) as f32);
+        let config = TrainingConfig {
+            layer_sizes: vec![2, 16, 2],
+            activations: default_activations(2),
+            iterations: 10,
+            alpha: 0.5,
+            dropout: vec![0.5],
+            ..TrainingConfig::default()
+        };
+        let without_dropout = TrainingConfig {
+            dropout: vec![0.0],
+            ..config.clone()
+        };
+
+        let first = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(6)),
+        );
+        let second = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(6)),
+        );
+        let plain = train(labels, data, &without_dropout, &mut create_rng(Some(6)));
+
+        assert_eq!(first, second);
+        assert_ne!(first, plain);
+    }
+
+    #[test]
+    fn test_config() {
+        let config = TrainingConfig {
+            layer_sizes: vec![784, 64, 32, 10],
+            activations: default_activations(3),
+            initializers: vec![Initializer::Uniform; 3],
+            regularization: vec![Default::default(); 3],
+            dropout: vec![0.5, 0.2],
+            ..TrainingConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        for dropout in [vec![0.5], vec![0.5, 1.0], vec![-0.1, 0.0]] {
+            let config = TrainingConfig {
+                dropout,
+                ..config.clone()
+            };
+            assert!(config.validate().is_err(), "{:?}", config.dropout);
+        }
+    }
+}
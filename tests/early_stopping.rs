@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::default_activations,
+        config::TrainingConfig,
+        dense_matrix::Matrix,
+        early_stopping::{EarlyStopping, EarlyStoppingState, Monitor},
+        matrix::create_rng,
+        network_functions::{continue_training, train, TrainingState},
+        types::NetworkParams,
+    };
+
+    fn get_data() -> (Matrix, Matrix) {
+        let labels = Matrix::from_fn(1, 20, |_, j| (j % 2) as f32);
+        let data = Matrix::from_fn(2, 20, |i, j| ((i + j) % 2) as f32);
+        (labels, data)
+    }
+
+    fn get_config(early_stopping: Option<EarlyStopping>) -> TrainingConfig {
+        TrainingConfig {
+            layer_sizes: vec![2, 4, 2],
+            activations: default_activations(2),
+            iterations: 20,
+            alpha: 0.5,
+            early_stopping,
+            ..TrainingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_monitor_names() {
+        assert_eq!("loss".parse::<Monitor>(), Ok(Monitor::Loss));
+        assert_eq!("val_loss".parse::<Monitor>(), Ok(Monitor::Loss));
+        assert_eq!(" Accuracy".parse::<Monitor>(), Ok(Monitor::Accuracy));
+        assert_eq!("val_acc".parse::<Monitor>(), Ok(Monitor::Accuracy));
+        assert!("f1".parse::<Monitor>().is_err());
+        assert_eq!(Monitor::Accuracy.to_string(), "accuracy");
+    }
+
+    #[test]
+    fn test_patience_and_min_delta() {
+        let early_stopping = EarlyStopping {
+            monitor: Monitor::Loss,
+            patience: 2,
+            min_delta: 0.1,
+        };
+        let network_params = NetworkParams::new();
+        let mut state = EarlyStoppingState::default();
+
+        for (iteration, loss) in [1.0, 0.85, 0.8, 0.7].into_iter().enumerate() {
+            state.update(&early_stopping, loss, &network_params, iteration + 1);
+        }
+        // 0.8 isn't 0.1 below 0.85, 0.7 is
+        assert_eq!(state.best, Some(0.7));
+        assert_eq!(state.best_iteration, 4);
+        assert!(!state.should_stop(&early_stopping));
+
+        state.update(&early_stopping, 0.65, &network_params, 5);
+        assert!(!state.should_stop(&early_stopping));
+        state.update(&early_stopping, 0.9, &network_params, 6);
+        assert!(state.should_stop(&early_stopping));
+        assert_eq!((state.best_iteration, state.wait), (4, 2));
+
+        let accuracy = EarlyStopping {
+            monitor: Monitor::Accuracy,
+            patience: 0,
+            min_delta: 0.0,
+        };
+        let mut state = EarlyStoppingState::default();
+        state.update(&accuracy, 50.0, &network_params, 1);
+        state.update(&accuracy, 60.0, &network_params, 2);
+        assert!(!state.should_stop(&accuracy));
+        state.update(&accuracy, 60.0, &network_params, 3);
+        assert!(state.should_stop(&accuracy));
+        assert_eq!(state.best, Some(60.0));
+    }
+
+    #[test]
+    fn test_stops_and_keeps_the_best_network() {
+        let (labels, data) = get_data();
+        // nothing improves by 1000, the first iteration stays the best
+        let config = get_config(Some(EarlyStopping {
+            monitor: Monitor::Loss,
+            patience: 3,
+            min_delta: 1000.0,
+        }));
+
+        let mut state = TrainingState::new(&config, &mut create_rng(Some(2)));
+        continue_training(
+            &mut state,
+            &labels,
+            &data,
+            Some((&labels, &data)),
+            &config,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        assert_eq!(state.iteration, 4);
+        assert_eq!(state.early_stopping.best_iteration, 1);
+
+        let first_iteration = TrainingConfig {
+            iterations: 1,
+            ..get_config(None)
+        };
+        assert_eq!(
+            state.network_params,
+            train(labels, data, &first_iteration, &mut create_rng(Some(2)))
+        );
+    }
+
+    #[test]
+    fn test_patience_longer_than_the_run() {
+        let (labels, data) = get_data();
+        let config = get_config(Some(EarlyStopping {
+            monitor: Monitor::Accuracy,
+            patience: 100,
+            min_delta: 0.0,
+        }));
+
+        let mut state = TrainingState::new(&config, &mut create_rng(Some(2)));
+        continue_training(&mut state, &labels, &data, None, &config, |_| Ok(())).unwrap();
+
+        assert_eq!(state.iteration, config.iterations);
+        assert_eq!(state.network_params, state.early_stopping.best_params);
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::default_activations,
+        config::{optimizer_from_name, parse_activations, parse_layer_sizes, TrainingConfig},
+        dense_matrix::Matrix,
+        error::Error,
+        io::load_network_params,
+        matrix::create_vec_from_csv,
+    };
+    use std::env;
+
+    fn read(content: &str) -> Result<Vec<Vec<f32>>, Error> {
+        create_vec_from_csv(
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(content.as_bytes()),
+        )
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        assert_eq!(
+            read("1,2,3\n4, 5 ,6\n").unwrap(),
+            vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]
+        );
+
+        let Err(Error::Parse { row, column, value }) = read("1,2,3\n4,x5,6\n") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((row, column, value.as_str()), (1, 1, "x5"));
+    }
+
+    #[test]
+    fn test_csv_error() {
+        let error = read("1,2,3\n4,5\n").unwrap_err();
+
+        assert!(matches!(error, Error::Csv(_)));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_shape_mismatch() {
+        let left = Matrix::zeroes(2, 3);
+
+        assert_eq!(left.try_dot(&Matrix::zeroes(3, 4)).unwrap().shape(), (2, 4));
+
+        let error = left.try_dot(&Matrix::zeroes(2, 4)).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::ShapeMismatch {
+                left: (2, 3),
+                right: (2, 4)
+            }
+        ));
+        assert_eq!(error.to_string(), "Incompatible matrix shapes: 2x3 and 2x4");
+    }
+
+    #[test]
+    fn test_missing_model() {
+        let model_dir = env::temp_dir().join(format!("rustnet_missing_{}", std::process::id()));
+
+        assert!(matches!(
+            load_network_params(&model_dir),
+            Err(Error::MissingModel(dir)) if dir == model_dir
+        ));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert_eq!(parse_layer_sizes("784, 10,10").unwrap(), vec![784, 10, 10]);
+        assert!(matches!(
+            parse_layer_sizes("784,ten,10"),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            parse_activations("relu,nope"),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            optimizer_from_name("lbfgs"),
+            Err(Error::InvalidConfig(_))
+        ));
+
+        let valid = TrainingConfig::default();
+        assert!(valid.validate().is_ok());
+
+        for invalid in [
+            TrainingConfig {
+                layer_sizes: vec![784],
+                activations: vec![],
+                ..TrainingConfig::default()
+            },
+            TrainingConfig {
+                layer_sizes: vec![784, 0, 10],
+                ..TrainingConfig::default()
+            },
+            TrainingConfig {
+                layer_sizes: vec![784, 32, 16, 10],
+                activations: default_activations(2),
+                ..TrainingConfig::default()
+            },
+            TrainingConfig {
+                validation_split: 1.0,
+                ..TrainingConfig::default()
+            },
+        ] {
+            assert!(matches!(invalid.validate(), Err(Error::InvalidConfig(_))));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        dense_matrix::Matrix,
+        error::Error,
+        evaluation::Evaluation,
+        network_functions::{check_dataset, get_accuracy, split_dataset},
+    };
+
+    // actual: 0 0 0 1 1 2, predicted: 0 0 1 1 2 2
+    fn get_evaluation() -> Evaluation {
+        let labels = Matrix::from(vec![vec![0.0, 0.0, 0.0, 1.0, 1.0, 2.0]]);
+
+        Evaluation::new(&labels, &[0, 0, 1, 1, 2, 2], 3)
+    }
+
+    #[test]
+    fn test_confusion_matrix() {
+        let evaluation = get_evaluation();
+
+        assert_eq!(
+            evaluation.confusion,
+            vec![vec![2, 1, 0], vec![0, 1, 1], vec![0, 0, 1]]
+        );
+        assert_eq!(evaluation.sample_count(), 6);
+        assert_eq!(evaluation.correct(), 4);
+    }
+
+    #[test]
+    fn test_accuracy_matches_get_accuracy() {
+        let labels = Matrix::from(vec![vec![0.0, 0.0, 0.0, 1.0, 1.0, 2.0]]);
+        let predictions = vec![0, 0, 1, 1, 2, 2];
+
+        assert_eq!(
+            get_evaluation().accuracy(),
+            get_accuracy(&labels, predictions)
+        );
+    }
+
+    #[test]
+    fn test_per_class_metrics() {
+        let evaluation = get_evaluation();
+
+        assert_eq!(evaluation.precision(0), 1.0);
+        assert_eq!(evaluation.recall(0), 2.0 / 3.0);
+        assert!((evaluation.f1(0) - 0.8).abs() < 1e-6);
+
+        assert_eq!(evaluation.precision(1), 0.5);
+        assert_eq!(evaluation.recall(1), 0.5);
+        assert_eq!(evaluation.f1(1), 0.5);
+
+        assert_eq!(evaluation.precision(2), 0.5);
+        assert_eq!(evaluation.recall(2), 1.0);
+    }
+
+    #[test]
+    fn test_metrics_without_samples() {
+        let labels = Matrix::from(vec![vec![0.0, 0.0]]);
+        let evaluation = Evaluation::new(&labels, &[0, 0], 2);
+
+        assert_eq!(evaluation.precision(1), 0.0);
+        assert_eq!(evaluation.recall(1), 0.0);
+        assert_eq!(evaluation.f1(1), 0.0);
+    }
+
+    #[test]
+    fn test_split_dataset() {
+        let labels = Matrix::from(vec![(0..10).map(|i| i as f32).collect::<Vec<f32>>()]);
+        let data = Matrix::from_fn(3, 10, |i, j| (i * 10 + j) as f32);
+
+        let ((train_labels, train_data), test_set) =
+            split_dataset(labels.clone(), data.clone(), 0.2).unwrap();
+        let (test_labels, test_data) = test_set.unwrap();
+
+        assert_eq!(
+            train_labels.row(0),
+            &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]
+        );
+        assert_eq!(test_labels.row(0), &[8.0, 9.0]);
+        assert_eq!(train_data.shape(), (3, 8));
+        assert_eq!(test_data.column(1), vec![9.0, 19.0, 29.0]);
+
+        let ((all_labels, _), test_set) = split_dataset(labels.clone(), data.clone(), 0.0).unwrap();
+        assert_eq!(all_labels, labels);
+        assert!(test_set.is_none());
+    }
+
+    #[test]
+    fn test_split_dataset_boundaries() {
+        let labels = Matrix::from(vec![(0..10).map(|i| i as f32).collect::<Vec<f32>>()]);
+        let data = Matrix::from_fn(3, 10, |i, j| (i * 10 + j) as f32);
+        let split =
+            |validation_split| split_dataset(labels.clone(), data.clone(), validation_split);
+
+        // one sample on either side is the smallest split that is accepted
+        let ((train_labels, _), test_set) = split(0.05).unwrap();
+        assert_eq!(train_labels.cols(), 9);
+        assert_eq!(test_set.unwrap().0.cols(), 1);
+
+        let ((train_labels, _), test_set) = split(0.94).unwrap();
+        assert_eq!(train_labels.cols(), 1);
+        assert_eq!(test_set.unwrap().0.cols(), 9);
+
+        // no held-out sample although a split was requested, or no training sample left
+        assert!(matches!(split(0.04), Err(Error::InvalidInput(_))));
+        assert!(matches!(split(0.96), Err(Error::InvalidInput(_))));
+
+        assert!(matches!(split(1.0), Err(Error::InvalidConfig(_))));
+        assert!(matches!(split(-0.1), Err(Error::InvalidConfig(_))));
+        assert!(matches!(split(f32::NAN), Err(Error::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_check_dataset() {
+        let labels = Matrix::from(vec![vec![0.0, 1.0, 1.0]]);
+        let data = Matrix::from_fn(4, 3, |i, j| (i + j) as f32);
+
+        assert!(check_dataset(&labels, &data, (8, 4), 2).is_ok());
+        assert!(matches!(
+            check_dataset(&labels, &data, (8, 5), 2),
+            Err(Error::ShapeMismatch {
+                left: (8, 5),
+                right: (4, 3)
+            })
+        ));
+
+        let out_of_range = Matrix::from(vec![vec![0.0, 2.0, 1.0]]);
+        let fractional = Matrix::from(vec![vec![0.0, 0.5, 1.0]]);
+        assert!(matches!(
+            check_dataset(&out_of_range, &data, (8, 4), 2),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            check_dataset(&fractional, &data, (8, 4), 2),
+            Err(Error::InvalidInput(_))
+        ));
+
+        let empty = Matrix::new(4, 0, vec![]);
+        assert!(matches!(
+            check_dataset(&Matrix::new(1, 0, vec![]), &empty, (8, 4), 2),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
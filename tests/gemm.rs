@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        dense_matrix::Matrix,
+        gemm::{blocked_dot_product, dot_product_strassen, BLOCK_SIZE},
+        matrix::dot_product,
+    };
+
+    fn get_matrix(rows: usize, cols: usize, seed: usize) -> Matrix {
+        Matrix::from_fn(rows, cols, |i, j| {
+            ((i * 31 + j * 17 + seed) % 23) as f32 / 7.0 - 1.5
+        })
+    }
+
+    #[test]
+    fn test_matches_dot_product() {
+        // shapes that are not multiples of the block size, the last one is large enough to be threaded
+        for (m, k, n) in [
+            (1, 1, 1),
+            (3, 5, 2),
+            (10, BLOCK_SIZE + 3, 2 * BLOCK_SIZE - 1),
+            (37, 300, 129),
+        ] {
+            let matrix_1 = get_matrix(m, k, 1);
+            let matrix_2 = get_matrix(k, n, 2);
+
+            let expected = dot_product(&matrix_1.to_nested(), &matrix_2.to_nested()).unwrap();
+
+            assert_eq!(
+                blocked_dot_product(&matrix_1, &matrix_2).to_nested(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_strassen_matches_blocked() {
+        // odd and even shapes so padding is needed at some of the levels
+        for (m, k, n) in [(64, 64, 64), (70, 91, 65), (33, 40, 100), (5, 200, 7)] {
+            let matrix_1 = get_matrix(m, k, 3);
+            let matrix_2 = get_matrix(k, n, 4);
+
+            let expected = blocked_dot_product(&matrix_1, &matrix_2);
+            let result = dot_product_strassen(&matrix_1, &matrix_2, 8);
+
+            assert_eq!(result.shape(), (m, n));
+            for (value, expected) in result.as_slice().iter().zip(expected.as_slice()) {
+                assert!(
+                    (value - expected).abs() < 1e-3 * expected.abs().max(1.0),
+                    "expected {expected}, got {value}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_strassen_is_exact_for_integers() {
+        let matrix_1 = Matrix::from_fn(40, 40, |i, j| ((i + 2 * j) % 5) as f32 - 2.0);
+        let matrix_2 = Matrix::from_fn(40, 40, |i, j| ((3 * i + j) % 7) as f32 - 3.0);
+
+        assert_eq!(
+            dot_product_strassen(&matrix_1, &matrix_2, 4),
+            blocked_dot_product(&matrix_1, &matrix_2)
+        );
+    }
+
+    #[test]
+    fn test_empty_product() {
+        let result = blocked_dot_product(&Matrix::zeroes(0, 3), &Matrix::zeroes(3, 4));
+
+        assert_eq!(result.shape(), (0, 4));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The number of columns in the first matrix must be equal to the number of rows in the second matrix!"
+    )]
+    fn test_invalid_dimensions() {
+        blocked_dot_product(&get_matrix(2, 3, 0), &get_matrix(2, 3, 0));
+    }
+}
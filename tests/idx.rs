@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+    use rustnet::common::{
+        error::Error,
+        idx::{is_idx_file, read_idx_images, read_idx_labels, read_mnist},
+        matrix::create_rng,
+        network_functions::prepare_data,
+    };
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn idx_bytes(dimensions: &[u32], values: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, 0x08, dimensions.len() as u8];
+        for dimension in dimensions {
+            bytes.extend_from_slice(&dimension.to_be_bytes());
+        }
+        bytes.extend_from_slice(values);
+        bytes
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn write_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("rustnet_idx_test_{}_{name}", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    // 3 images of 2x2 pixels
+    fn get_images() -> Vec<u8> {
+        idx_bytes(&[3, 2, 2], &[0, 1, 2, 3, 10, 20, 30, 40, 255, 0, 255, 0])
+    }
+
+    #[test]
+    fn test_read_images_and_labels() {
+        let images = write_file("images", &get_images());
+        let labels = write_file("labels.gz", &gzip(&idx_bytes(&[3], &[7, 0, 9])));
+
+        assert_eq!(
+            read_idx_images(&images).unwrap(),
+            vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![10.0, 20.0, 30.0, 40.0],
+                vec![255.0, 0.0, 255.0, 0.0]
+            ]
+        );
+        assert_eq!(read_idx_labels(&labels).unwrap(), vec![7.0, 0.0, 9.0]);
+        assert!(is_idx_file(&images).unwrap());
+        assert!(is_idx_file(&labels).unwrap());
+
+        let dataset = read_mnist(&images, &labels).unwrap();
+        assert_eq!(dataset[1], vec![0.0, 10.0, 20.0, 30.0, 40.0]);
+
+        let (labels_matrix, data) = prepare_data(dataset, &mut create_rng(Some(0)));
+        assert_eq!(labels_matrix.shape(), (1, 3));
+        assert_eq!(data.shape(), (4, 3));
+
+        fs::remove_file(images).unwrap();
+        fs::remove_file(labels).unwrap();
+    }
+
+    #[test]
+    fn test_gzip_matches_raw() {
+        let raw = write_file("raw", &get_images());
+        let compressed = write_file("compressed.gz", &gzip(&get_images()));
+
+        assert_eq!(
+            read_idx_images(&raw).unwrap(),
+            read_idx_images(&compressed).unwrap()
+        );
+
+        fs::remove_file(raw).unwrap();
+        fs::remove_file(compressed).unwrap();
+    }
+
+    #[test]
+    fn test_csv_is_not_idx() {
+        let csv = write_file("dataset.csv", b"7,0,1,2,3\n");
+
+        assert!(!is_idx_file(&csv).unwrap());
+        assert!(matches!(read_idx_images(&csv), Err(Error::InvalidInput(_))));
+
+        fs::remove_file(csv).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_headers() {
+        let mut wrong_type = get_images();
+        wrong_type[2] = 0x0d;
+
+        let cases = [
+            ("wrong_type", wrong_type),
+            ("labels_as_images", idx_bytes(&[3], &[7, 0, 9])),
+            ("truncated_header", get_images()[..10].to_vec()),
+            ("missing_pixels", idx_bytes(&[3, 2, 2], &[0; 11])),
+            ("extra_pixels", idx_bytes(&[3, 2, 2], &[0; 13])),
+            (
+                "overflowing_count",
+                idx_bytes(&[u32::MAX, u32::MAX, 3], &[0; 12]),
+            ),
+            ("empty_images", idx_bytes(&[u32::MAX, 0, 28], &[])),
+        ];
+
+        for (name, bytes) in cases {
+            let path = write_file(name, &bytes);
+            assert!(
+                matches!(read_idx_images(&path), Err(Error::InvalidInput(_))),
+                "{name}"
+            );
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_label_count_mismatch() {
+        let images = write_file("mismatch_images", &get_images());
+        let labels = write_file("mismatch_labels", &idx_bytes(&[2], &[7, 0]));
+
+        assert!(matches!(
+            read_mnist(&images, &labels),
+            Err(Error::InvalidInput(_))
+        ));
+
+        fs::remove_file(images).unwrap();
+        fs::remove_file(labels).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::Activation,
+        config::parse_initializers,
+        dense_matrix::Matrix,
+        initializer::Initializer,
+        matrix::{create_network_params, create_rng},
+        network_functions::forward_propagation,
+    };
+
+    const INITIALIZERS: [Initializer; 7] = [
+        Initializer::Uniform,
+        Initializer::HeNormal,
+        Initializer::HeUniform,
+        Initializer::XavierNormal,
+        Initializer::XavierUniform,
+        Initializer::LecunNormal,
+        Initializer::LecunUniform,
+    ];
+
+    fn std_dev(matrix: &Matrix) -> f32 {
+        let mean = matrix.mean();
+        let variance = matrix
+            .as_slice()
+            .iter()
+            .map(|x| (x - mean).powi(2))
+            .sum::<f32>()
+            / matrix.as_slice().len() as f32;
+
+        variance.sqrt()
+    }
+
+    #[test]
+    fn test_names() {
+        for initializer in INITIALIZERS {
+            assert_eq!(
+                initializer.to_string().parse::<Initializer>(),
+                Ok(initializer)
+            );
+        }
+
+        assert_eq!(
+            parse_initializers("he, Glorot_Uniform,lecun").unwrap(),
+            vec![
+                Initializer::HeNormal,
+                Initializer::XavierUniform,
+                Initializer::LecunNormal
+            ]
+        );
+        assert!("zeros".parse::<Initializer>().is_err());
+    }
+
+    #[test]
+    fn test_scale_follows_fan_in_and_fan_out() {
+        let (units, inputs) = (200, 300);
+        let (fan_in, fan_out) = (inputs as f32, units as f32);
+
+        let expected = [
+            (Initializer::Uniform, (1.0f32 / 12.0).sqrt(), 0.5),
+            (Initializer::HeNormal, (2.0 / fan_in).sqrt(), f32::INFINITY),
+            (
+                Initializer::HeUniform,
+                (2.0 / fan_in).sqrt(),
+                (6.0 / fan_in).sqrt(),
+            ),
+            (
+                Initializer::XavierNormal,
+                (2.0 / (fan_in + fan_out)).sqrt(),
+                f32::INFINITY,
+            ),
+            (
+                Initializer::XavierUniform,
+                (2.0 / (fan_in + fan_out)).sqrt(),
+                (6.0 / (fan_in + fan_out)).sqrt(),
+            ),
+            (
+                Initializer::LecunNormal,
+                (1.0 / fan_in).sqrt(),
+                f32::INFINITY,
+            ),
+            (
+                Initializer::LecunUniform,
+                (1.0 / fan_in).sqrt(),
+                (3.0 / fan_in).sqrt(),
+            ),
+        ];
+
+        for (initializer, std, limit) in expected {
+            let (weights, biases) = initializer.init(units, inputs, &mut create_rng(Some(3)));
+
+            assert_eq!(weights.shape(), (units, inputs));
+            assert_eq!(biases.shape(), (units, 1));
+            assert!(weights.mean().abs() < std * 0.05, "{initializer}");
+            assert!(
+                (std_dev(&weights) / std - 1.0).abs() < 0.05,
+                "{initializer}"
+            );
+            assert!(
+                weights.max() <= limit && weights.min() >= -limit,
+                "{initializer}"
+            );
+
+            if initializer == Initializer::Uniform {
+                assert!(biases.max() > 0.0 && biases.max() <= 0.5);
+            } else {
+                assert_eq!(biases, Matrix::zeroes(units, 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_per_layer_initializers() {
+        let network_params = create_network_params(
+            &[4, 3, 2],
+            &[Activation::Relu, Activation::Softmax],
+            &[Initializer::HeNormal, Initializer::Uniform],
+            &mut create_rng(Some(0)),
+        );
+
+        assert_eq!(network_params[0].biases, Matrix::zeroes(3, 1));
+        assert_ne!(network_params[1].biases, Matrix::zeroes(2, 1));
+    }
+
+    #[test]
+    fn test_he_keeps_deep_relu_activations_stable() {
+        let layer_sizes = [256; 11];
+        let activations = [Activation::Relu; 10];
+        let input = Matrix::from_fn(256, 32, |i, j| ((i * 31 + j * 17) % 11) as f32 / 10.0 - 0.5);
+
+        let output_scale = |initializer| {
+            let network_params = create_network_params(
+                &layer_sizes,
+                &activations,
+                &[initializer; 10],
+                &mut create_rng(Some(1)),
+            );
+            let forward_cache = forward_propagation(&network_params, &input);
+            std_dev(&forward_cache.last().unwrap().activation) / std_dev(&input)
+        };
+
+        let he = output_scale(Initializer::HeNormal);
+        assert!(he > 0.1 && he < 10.0, "{he}");
+        assert!(output_scale(Initializer::Uniform) > 1000.0 * he);
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::{default_activations, Activation},
+        error::Error,
+        initializer::default_initializers,
+        io::{
+            check_results_exist, load_model, load_network_params, migrate_csv_model, save_model,
+            save_predictors, MODEL_FILE,
+        },
+        matrix::{create_network_params, create_rng},
+        model_file::ModelMetadata,
+    };
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn get_model_dir(name: &str) -> PathBuf {
+        let model_dir =
+            env::temp_dir().join(format!("rustnet_io_test_{}_{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&model_dir);
+        model_dir
+    }
+
+    #[test]
+    fn test_models_side_by_side() {
+        let (dir_1, dir_2) = (get_model_dir("small"), get_model_dir("deep"));
+
+        let small = create_network_params(
+            &[4, 3, 2],
+            &default_activations(2),
+            &default_initializers(2),
+            &mut create_rng(Some(0)),
+        );
+        let deep = create_network_params(
+            &[4, 5, 3, 2],
+            &[
+                Activation::Tanh,
+                Activation::LeakyRelu(0.1),
+                Activation::Softmax,
+            ],
+            &default_initializers(3),
+            &mut create_rng(Some(0)),
+        );
+
+        assert!(!check_results_exist(&dir_1));
+
+        save_predictors(&small, &dir_1).unwrap();
+        save_predictors(&deep, &dir_2).unwrap();
+
+        assert!(check_results_exist(&dir_1));
+        assert!(check_results_exist(&dir_2));
+        assert_eq!(load_network_params(&dir_1).unwrap(), small);
+        assert_eq!(load_network_params(&dir_2).unwrap(), deep);
+
+        fs::remove_dir_all(dir_1).unwrap();
+        fs::remove_dir_all(dir_2).unwrap();
+    }
+
+    #[test]
+    fn test_overwrite_removes_stale_layers() {
+        let model_dir = get_model_dir("overwrite");
+
+        let deep = create_network_params(
+            &[4, 5, 3, 2],
+            &default_activations(3),
+            &default_initializers(3),
+            &mut create_rng(Some(0)),
+        );
+        let small = create_network_params(
+            &[4, 2],
+            &default_activations(1),
+            &default_initializers(1),
+            &mut create_rng(Some(0)),
+        );
+
+        save_predictors(&deep, &model_dir).unwrap();
+        save_predictors(&small, &model_dir).unwrap();
+
+        assert_eq!(load_network_params(&model_dir).unwrap(), small);
+        assert!(!model_dir.join("w_2.csv").exists());
+
+        // a directory in place of a layer file can't be written, the error is returned
+        fs::remove_file(model_dir.join("w_1.csv")).unwrap();
+        fs::create_dir(model_dir.join("w_1.csv")).unwrap();
+        assert!(matches!(
+            save_predictors(&small, &model_dir),
+            Err(Error::Io(_))
+        ));
+
+        fs::remove_dir_all(model_dir).unwrap();
+    }
+
+    #[test]
+    fn test_binary_model() {
+        let model_dir = get_model_dir("binary");
+        let network_params = create_network_params(
+            &[4, 3, 2],
+            &default_activations(2),
+            &default_initializers(2),
+            &mut create_rng(Some(0)),
+        );
+        let metadata = ModelMetadata {
+            iterations: 10,
+            alpha: 0.1,
+            ..ModelMetadata::default()
+        };
+
+        save_model(&network_params, &metadata, &model_dir).unwrap();
+
+        assert!(check_results_exist(&model_dir));
+        assert_eq!(
+            load_model(&model_dir).unwrap(),
+            (network_params.clone(), metadata)
+        );
+        assert_eq!(load_network_params(&model_dir).unwrap(), network_params);
+
+        fs::write(model_dir.join(MODEL_FILE), b"RNET corrupted").unwrap();
+        let error = load_model(&model_dir).unwrap_err();
+        assert!(matches!(error, Error::CorruptModel(_)), "{error}");
+        assert!(error.to_string().contains(MODEL_FILE), "{error}");
+
+        fs::remove_dir_all(model_dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_csv_model() {
+        let model_dir = get_model_dir("migrate");
+        let network_params = create_network_params(
+            &[4, 3, 2],
+            &default_activations(2),
+            &default_initializers(2),
+            &mut create_rng(Some(0)),
+        );
+
+        assert!(migrate_csv_model(&model_dir).is_err());
+
+        save_predictors(&network_params, &model_dir).unwrap();
+        migrate_csv_model(&model_dir).unwrap();
+
+        assert!(model_dir.join(MODEL_FILE).is_file());
+        assert_eq!(
+            load_model(&model_dir).unwrap(),
+            (network_params, ModelMetadata::default())
+        );
+
+        fs::remove_dir_all(model_dir).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::Activation,
+        dense_matrix::Matrix,
+        loss::{log_softmax, Loss},
+        types::LayerOutput,
+    };
+
+    const LOSSES: [Loss; 4] = [
+        Loss::CrossEntropy,
+        Loss::MeanSquaredError,
+        Loss::BinaryCrossEntropy,
+        Loss::Hinge,
+    ];
+
+    fn get_output(activation: Activation, z: Matrix) -> LayerOutput {
+        LayerOutput {
+            dropout_mask: None,
+            activation: activation.forward(&z),
+            z,
+        }
+    }
+
+    // 3 classes x 2 samples, labels 0 and 2
+    fn get_expected() -> Matrix {
+        Matrix::from(vec![vec![1.0, 0.0], vec![0.0, 0.0], vec![0.0, 1.0]])
+    }
+
+    fn get_logits() -> Matrix {
+        Matrix::from(vec![vec![0.5, -0.3], vec![1.2, 0.8], vec![-0.7, 0.1]])
+    }
+
+    #[test]
+    fn test_cross_entropy() {
+        let output = get_output(Activation::Softmax, get_logits());
+        let expected = get_expected();
+
+        let manual = -(output.activation[(0, 0)].ln() + output.activation[(2, 1)].ln()) / 2.0;
+        let loss = Loss::CrossEntropy.compute(Activation::Softmax, &output, &expected);
+
+        assert!((loss - manual).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_log_softmax_is_stable() {
+        let z = Matrix::from(vec![vec![1000.0, -1000.0], vec![0.0, -1010.0]]);
+        let result = log_softmax(&z);
+
+        assert!(result.as_slice().iter().all(|value| value.is_finite()));
+        assert!(result[(0, 0)].abs() < 1e-6);
+        assert!((result[(1, 0)] + 1000.0).abs() < 1e-3);
+        assert!((result[(1, 1)] + 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_loss_values() {
+        let expected = get_expected();
+        let identity = get_output(Activation::Identity, get_logits());
+
+        // ((0.5 - 1)^2 + 1.2^2 + 0.7^2 + 0.3^2 + 0.8^2 + (0.1 - 1)^2) / 2
+        let mse = Loss::MeanSquaredError.compute(Activation::Identity, &identity, &expected);
+        assert!((mse - 1.86).abs() < 1e-5);
+
+        // sample 1: 1 + 1.2 - 0.5 = 1.7, sample 2: 1 - 0.3 - 0.1 + 1 + 0.8 - 0.1 = 2.3
+        let hinge = Loss::Hinge.compute(Activation::Identity, &identity, &expected);
+        assert!((hinge - 2.0).abs() < 1e-5);
+
+        let sigmoid = get_output(Activation::Sigmoid, get_logits());
+        let fused = Loss::BinaryCrossEntropy.compute(Activation::Sigmoid, &sigmoid, &expected);
+        let unfused = Loss::BinaryCrossEntropy.compute(Activation::Identity, &sigmoid, &expected);
+        assert!((fused - unfused).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_difference() {
+        let expected = get_expected();
+        let step = 1e-2;
+
+        for activation in [
+            Activation::Softmax,
+            Activation::Sigmoid,
+            Activation::Identity,
+        ] {
+            for loss in LOSSES {
+                // the log based losses need outputs between 0 and 1
+                if activation == Activation::Identity
+                    && matches!(loss, Loss::CrossEntropy | Loss::BinaryCrossEntropy)
+                {
+                    continue;
+                }
+
+                let gradient =
+                    loss.gradient(activation, &get_output(activation, get_logits()), &expected);
+
+                for i in 0..3 {
+                    for j in 0..2 {
+                        let mut z_plus = get_logits();
+                        let mut z_minus = get_logits();
+                        z_plus[(i, j)] += step;
+                        z_minus[(i, j)] -= step;
+
+                        // compute() averages over the 2 samples, gradient() is the summed loss
+                        let numeric =
+                            (loss.compute(activation, &get_output(activation, z_plus), &expected)
+                                - loss.compute(
+                                    activation,
+                                    &get_output(activation, z_minus),
+                                    &expected,
+                                ))
+                                * 2.0
+                                / (2.0 * step);
+
+                        assert!(
+                            (gradient[(i, j)] - numeric).abs() < 1e-2 * numeric.abs().max(1.0),
+                            "{loss} with {activation}: expected {numeric}, got {}",
+                            gradient[(i, j)]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for loss in LOSSES {
+            assert_eq!(loss.to_string().parse::<Loss>(), Ok(loss));
+        }
+        assert!("kl_divergence".parse::<Loss>().is_err());
+    }
+}
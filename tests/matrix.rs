@@ -1,40 +1,59 @@
 #[cfg(test)]
 mod tests {
     use rustnet::common::{
+        activation::{default_activations, Activation},
+        dense_matrix::Matrix,
+        error::Error,
+        initializer::default_initializers,
         integration_test_vars::{
             get_b_1_test, get_b_2_test, get_image_label_test, get_image_test, get_w_1_test,
             get_w_2_test,
         },
+        loss::Loss,
         matrix::{
-            dot_product, get_nth_column, linear_op, matrix_avg, matrix_max, matrix_min,
-            matrix_multiply, matrix_subtract, row_sum, transpose, Operation,
+            create_network_params, create_rng, dot_product, get_nth_column, linear_op, matrix_avg,
+            matrix_max, matrix_min, matrix_multiply, matrix_subtract, row_sum, select_columns,
+            transpose, Operation,
         },
         network_functions::{
             back_propagation, forward_propagation, get_predictions, relu, softmax,
             transform_labels_to_network_output,
         },
         numpy_vec::NumpyVec,
+        types::{Layer, NetworkParams},
     };
 
+    fn get_network_params_test() -> NetworkParams {
+        vec![
+            Layer {
+                weights: Matrix::from(get_w_1_test()),
+                biases: Matrix::from(get_b_1_test()),
+                activation: Activation::Relu,
+            },
+            Layer {
+                weights: Matrix::from(get_w_2_test()),
+                biases: Matrix::from(get_b_2_test()),
+                activation: Activation::Softmax,
+            },
+        ]
+    }
+
     #[test]
     fn test_dot_product() {
         let matrix1 = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
         let matrix2 = vec![vec![7.0, 8.0], vec![9.0, 10.0], vec![11.0, 12.0]];
         let expected = vec![vec![58.0, 64.0], vec![139.0, 154.0]];
-        assert_eq!(dot_product(&matrix1, &matrix2), expected);
+        assert_eq!(dot_product(&matrix1, &matrix2).unwrap(), expected);
 
         let matrix3 = vec![vec![1.0, 2.0], vec![-0.1, 0.2]];
         let matrix4 = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
         assert_eq!(
-            dot_product(&matrix3, &matrix4),
+            dot_product(&matrix3, &matrix4).unwrap(),
             vec![vec![7., 10.], vec![0.5, 0.6]]
         );
     }
 
     #[test]
-    #[should_panic(
-        expected = "The number of columns in the first matrix must be equal to the number of rows in the second matrix"
-    )]
     fn test_dot_product_invalid_dimensions() {
         let matrix1 = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
         let matrix2 = vec![
@@ -43,7 +62,26 @@ mod tests {
             vec![11.0, 12.0],
             vec![13.0, 14.0],
         ];
-        dot_product(&matrix1, &matrix2);
+        assert!(matches!(
+            dot_product(&matrix1, &matrix2),
+            Err(Error::ShapeMismatch {
+                left: (2, 3),
+                right: (4, 2)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_dot_product_empty_inner_dimension() {
+        assert_eq!(
+            Matrix::zeroes(2, 0).dot(&Matrix::zeroes(0, 3)),
+            Matrix::zeroes(2, 3)
+        );
+        // large enough for the multi-threaded path
+        assert_eq!(
+            Matrix::zeroes(1024, 0).dot(&Matrix::zeroes(0, 1024)),
+            Matrix::zeroes(1024, 1024)
+        );
     }
 
     #[test]
@@ -78,9 +116,16 @@ mod tests {
     }
 
     #[test]
-    fn test_softmax() {
+    fn test_select_columns() {
         let x = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
-        let result = softmax(&x);
+        let result = select_columns(&x, &[2, 0]);
+        assert_eq!(result, vec![vec![3.0, 1.0], vec![6.0, 4.0]]);
+    }
+
+    #[test]
+    fn test_softmax() {
+        let x = Matrix::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        let result = softmax(&x).to_nested();
         assert_eq!(
             result,
             vec![
@@ -89,33 +134,57 @@ mod tests {
             ]
         );
 
-        let y = vec![
+        let y = Matrix::from(vec![
             vec![-5.0, -9.0, -1.0],
             vec![-0.5, -0.1, -0.9],
             vec![1.0, 5.0, 9.0],
-        ];
+        ]);
+
+        assert_eq!(
+            softmax(&y).to_nested(),
+            vec![
+                vec![0.002022466, 8.2648916e-7, 4.539559e-5],
+                vec![0.18205658, 0.0060597975, 5.0169907e-5],
+                vec![0.81592095, 0.9939394, 0.9999044]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_softmax_extreme_logits() {
+        // a single sample with huge logits must not underflow the other columns
+        let x = Matrix::from(vec![vec![1000.0, 1.0, -1000.0], vec![999.0, 2.0, -1001.0]]);
+        let result = softmax(&x);
 
+        assert!(result.as_slice().iter().all(|value| value.is_finite()));
+        assert_eq!(result.col_sum(), vec![1.0, 1.0, 1.0]);
         assert_eq!(
-            softmax(&y),
+            result,
+            softmax(&Matrix::from(vec![
+                vec![1.0, 1.0, 1.0],
+                vec![0.0, 2.0, 0.0]
+            ]))
+        );
+        assert_eq!(
+            result.to_nested(),
             vec![
-                vec![0.002022466, 8.264891e-7, 4.539559e-5],
-                vec![0.18205658, 0.006059794, 5.0169907e-5],
-                vec![0.815921, 0.9939394, 0.9999044]
+                vec![0.7310586, 0.26894143, 0.7310586],
+                vec![0.26894143, 0.7310586, 0.26894143]
             ]
         );
     }
 
     #[test]
     fn test_get_predictions() {
-        let x = vec![vec![1.0, 7.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let x = Matrix::from(vec![vec![1.0, 7.0, 3.0], vec![4.0, 5.0, 6.0]]);
         let result = get_predictions(&x);
         assert_eq!(result, vec![1, 0, 1]);
 
-        let matrix = vec![
+        let matrix = Matrix::from(vec![
             vec![1.0, 5.0, 3.0],
             vec![4.0, 6.0, 2.0],
             vec![7.0, 2.0, 9.0],
-        ];
+        ]);
         assert_eq!(get_predictions(&matrix), vec![2, 1, 2]);
     }
 
@@ -139,11 +208,11 @@ mod tests {
 
     #[test]
     fn test_transform_labels_to_network_output() {
-        let x = vec![vec![1.0, 2.0, 3.0]];
+        let x = Matrix::from(vec![vec![1.0, 2.0, 3.0]]);
         let result = transform_labels_to_network_output(&x);
 
         assert_eq!(
-            result,
+            result.to_nested(),
             vec![
                 vec![0.0, 0.0, 0.0],
                 vec![1.0, 0.0, 0.0],
@@ -177,16 +246,16 @@ mod tests {
 
     #[test]
     fn test_relu() {
-        let x = vec![
+        let x = Matrix::from(vec![
             vec![-1.0, -2.0, -3.0],
             vec![-0.1, -0.2, -0.3],
             vec![1.0, 2.0, 3.0],
             vec![0.1, 0.2, 0.3],
-        ];
+        ]);
         let result = relu(&x);
 
         assert_eq!(
-            result,
+            result.to_nested(),
             vec![
                 vec![0.0, 0.0, 0.0],
                 vec![0.0, 0.0, 0.0],
@@ -210,18 +279,12 @@ mod tests {
 
     #[test]
     fn test_forward_prop() {
-        let (_z_1, _activation_1, _z_2, activation_2) = forward_propagation(
-            (
-                get_w_1_test(),
-                get_b_1_test(),
-                get_w_2_test(),
-                get_b_2_test(),
-            ),
-            &get_image_test(),
-        );
+        let forward_cache =
+            forward_propagation(&get_network_params_test(), &Matrix::from(get_image_test()));
+        let activation_2 = &forward_cache.last().unwrap().activation;
 
-        let _activation_2_min = matrix_min(&activation_2);
-        let _activation_2_max = matrix_max(&activation_2);
+        let _activation_2_min = matrix_min(&activation_2.to_nested());
+        let _activation_2_max = matrix_max(&activation_2.to_nested());
 
         assert_eq!(_activation_2_min, 0.00323996);
         assert_eq!(_activation_2_max, 0.53724295);
@@ -229,59 +292,92 @@ mod tests {
 
     #[test]
     fn test_back_prop() {
-        let (_z_1, _activation_1, _z_2, activation_2) = forward_propagation(
-            (
-                get_w_1_test(),
-                get_b_1_test(),
-                get_w_2_test(),
-                get_b_2_test(),
-            ),
-            &get_image_test(),
-        );
+        let network_params = get_network_params_test();
+        let forward_cache = forward_propagation(&network_params, &Matrix::from(get_image_test()));
+        let activation_2 = &forward_cache.last().unwrap().activation;
 
-        let _activation_2_min = matrix_min(&activation_2);
-        let _activation_2_max = matrix_max(&activation_2);
+        let _activation_2_min = matrix_min(&activation_2.to_nested());
+        let _activation_2_max = matrix_max(&activation_2.to_nested());
 
         assert_eq!(_activation_2_min, 0.00323996);
         assert_eq!(_activation_2_max, 0.53724295);
 
-        let (delta_w_1, delta_b_1, delta_w_2, delta_b_2) = back_propagation(
-            (_z_1, _activation_1, _z_2, activation_2),
-            get_w_2_test(),
-            get_image_label_test(),
-            &get_image_test(),
+        let gradients = back_propagation(
+            &network_params,
+            &forward_cache,
+            &Matrix::from(get_image_label_test()),
+            &Matrix::from(get_image_test()),
+            Loss::CrossEntropy,
         );
+        let (delta_w_1, delta_b_1) = (&gradients[0].weights, &gradients[0].biases);
+        let (delta_w_2, delta_b_2) = (&gradients[1].weights, &gradients[1].biases);
 
-        let _delta_w_2_min = matrix_min(&delta_w_2);
-        let _delta_w_2_max = matrix_max(&delta_w_2);
+        let _delta_w_2_min = matrix_min(&delta_w_2.to_nested());
+        let _delta_w_2_max = matrix_max(&delta_w_2.to_nested());
 
         assert_eq!(_delta_w_2_min, -4.479972);
         assert_eq!(_delta_w_2_max, 2.558414);
 
-        let _delta_w_1_avg = matrix_avg(&delta_w_1);
-        let _delta_w_1_min = matrix_min(&delta_w_1);
-        let _delta_w_1_max = matrix_max(&delta_w_1);
+        let _delta_w_1_avg = matrix_avg(&delta_w_1.to_nested());
+        let _delta_w_1_min = matrix_min(&delta_w_1.to_nested());
+        let _delta_w_1_max = matrix_max(&delta_w_1.to_nested());
 
         assert_eq!(_delta_w_1_min, -0.39199317);
         assert_eq!(_delta_w_1_max, 0.5273504);
 
-        let _delta_b_1_avg = matrix_avg(&delta_b_1);
-        let _delta_b_1_min = matrix_min(&delta_b_1);
-        let _delta_b_1_max = matrix_max(&delta_b_1);
+        let _delta_b_1_avg = matrix_avg(&delta_b_1.to_nested());
+        let _delta_b_1_min = matrix_min(&delta_b_1.to_nested());
+        let _delta_b_1_max = matrix_max(&delta_b_1.to_nested());
 
         assert_eq!(_delta_b_1_avg, 0.07719006);
         assert_eq!(_delta_b_1_min, -0.39199317);
         assert_eq!(_delta_b_1_max, 0.5273504);
 
-        let _delta_b_2_avg = matrix_avg(&delta_b_2);
-        let _delta_b_2_min = matrix_min(&delta_b_2);
-        let _delta_b_2_max = matrix_max(&delta_b_2);
+        let _delta_b_2_avg = matrix_avg(&delta_b_2.to_nested());
+        let _delta_b_2_min = matrix_min(&delta_b_2.to_nested());
+        let _delta_b_2_max = matrix_max(&delta_b_2.to_nested());
 
         assert_eq!(_delta_b_2_avg, 5.9604646e-9);
         assert_eq!(_delta_b_2_min, -0.9407521);
         assert_eq!(_delta_b_2_max, 0.53724295);
     }
 
+    #[test]
+    fn test_multi_layer_network() {
+        let network_params = create_network_params(
+            &[784, 16, 8, 10],
+            &default_activations(3),
+            &default_initializers(3),
+            &mut create_rng(Some(0)),
+        );
+        let shapes: Vec<(usize, usize)> = network_params
+            .iter()
+            .map(|layer| layer.weights.shape())
+            .collect();
+
+        assert_eq!(shapes, vec![(16, 784), (8, 16), (10, 8)]);
+
+        let forward_cache = forward_propagation(&network_params, &Matrix::from(get_image_test()));
+        let output = &forward_cache.last().unwrap().activation;
+
+        assert_eq!(forward_cache.len(), 3);
+        assert_eq!(output.shape(), (10, 1));
+        assert!((output.col_sum()[0] - 1.0).abs() < 1e-5);
+
+        let gradients = back_propagation(
+            &network_params,
+            &forward_cache,
+            &Matrix::from(get_image_label_test()),
+            &Matrix::from(get_image_test()),
+            Loss::CrossEntropy,
+        );
+
+        for (gradient, layer) in gradients.iter().zip(&network_params) {
+            assert_eq!(gradient.weights.shape(), layer.weights.shape());
+            assert_eq!(gradient.biases.shape(), layer.biases.shape());
+        }
+    }
+
     #[test]
     fn test_operator_overload() {
         let x = NumpyVec(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
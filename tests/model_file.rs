@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::Activation,
+        dense_matrix::Matrix,
+        error::Error,
+        initializer::default_initializers,
+        matrix::{create_network_params, create_rng},
+        model_file::{crc32, decode, encode, ModelMetadata, FORMAT_VERSION, MAGIC},
+        types::{Layer, NetworkParams},
+    };
+
+    fn get_network_params() -> NetworkParams {
+        create_network_params(
+            &[6, 4, 3],
+            &[Activation::LeakyRelu(0.1), Activation::Softmax],
+            &default_initializers(2),
+            &mut create_rng(Some(0)),
+        )
+    }
+
+    fn get_metadata() -> ModelMetadata {
+        ModelMetadata {
+            iterations: 200,
+            alpha: 0.15,
+            batch_size: 64,
+            loss: "cross_entropy".to_owned(),
+            accuracy: Some(91.25),
+        }
+    }
+
+    // replaces the trailing checksum after the content was modified on purpose
+    fn reseal(bytes: &mut Vec<u8>) {
+        bytes.truncate(bytes.len() - 4);
+        let checksum = crc32(bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let network_params = get_network_params();
+        let bytes = encode(&network_params, &get_metadata());
+
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(decode(&bytes).unwrap(), (network_params, get_metadata()));
+
+        let (_, metadata) =
+            decode(&encode(&get_network_params(), &ModelMetadata::default())).unwrap();
+        assert_eq!(metadata, ModelMetadata::default());
+    }
+
+    #[test]
+    fn test_corrupted_file() {
+        let mut bytes = encode(&get_network_params(), &get_metadata());
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xFF;
+
+        let error = decode(&bytes).unwrap_err();
+        assert!(error.to_string().contains("checksum"), "{error}");
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let bytes = encode(&get_network_params(), &get_metadata());
+
+        assert!(matches!(
+            decode(b"not a model"),
+            Err(Error::CorruptModel(_))
+        ));
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut truncated = bytes[..bytes.len() - 20].to_vec();
+        reseal(&mut truncated);
+        assert!(decode(&truncated)
+            .unwrap_err()
+            .to_string()
+            .contains("truncated"));
+
+        let mut newer = bytes.clone();
+        newer[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        reseal(&mut newer);
+        assert!(decode(&newer).unwrap_err().to_string().contains("version"));
+
+        let mut unversioned = bytes.clone();
+        unversioned[4..8].copy_from_slice(&0u32.to_le_bytes());
+        reseal(&mut unversioned);
+        assert!(decode(&unversioned)
+            .unwrap_err()
+            .to_string()
+            .contains("version"));
+    }
+
+    #[test]
+    fn test_invalid_layers() {
+        let empty = encode(&vec![], &get_metadata());
+        assert!(decode(&empty)
+            .unwrap_err()
+            .to_string()
+            .contains("no layers"));
+
+        // the second layer takes 3 inputs although the first one has 4 units
+        let mut network_params = get_network_params();
+        network_params[1] = Layer {
+            weights: Matrix::from_fn(3, 3, |i, j| (i + j) as f32),
+            biases: Matrix::from_fn(3, 1, |i, _| i as f32),
+            activation: Activation::Softmax,
+        };
+        let unchained = encode(&network_params, &get_metadata());
+        assert!(decode(&unchained)
+            .unwrap_err()
+            .to_string()
+            .contains("layer 2 takes 3 inputs"));
+
+        // an output layer without units would panic when predicting
+        network_params[1] = Layer {
+            weights: Matrix::new(0, 4, vec![]),
+            biases: Matrix::new(0, 1, vec![]),
+            activation: Activation::Softmax,
+        };
+        let zero_sized = encode(&network_params, &get_metadata());
+        assert!(decode(&zero_sized)
+            .unwrap_err()
+            .to_string()
+            .contains("layer 2 has 0x4 weights"));
+
+        // a huge layer count fails on the missing layers instead of allocating for them
+        let mut huge = empty.clone();
+        let count = huge.len() - 8;
+        huge[count..count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        reseal(&mut huge);
+        assert!(decode(&huge).unwrap_err().to_string().contains("truncated"));
+    }
+}
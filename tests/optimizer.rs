@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::Activation,
+        config::TrainingConfig,
+        dense_matrix::Matrix,
+        error::Error,
+        optimizer::{OptimizerKind, OptimizerState},
+        types::{Layer, NetworkParams},
+    };
+
+    fn get_single_param(value: f32) -> NetworkParams {
+        vec![Layer {
+            weights: Matrix::filled(1, 1, value),
+            biases: Matrix::filled(1, 1, value),
+            activation: Activation::Identity,
+        }]
+    }
+
+    // runs `steps` updates with a constant gradient of 0.5 and alpha of 0.1 on a weight of 1.0
+    fn run_steps(kind: OptimizerKind, steps: usize) -> f32 {
+        let mut optimizer = kind.build();
+        let mut network_params = get_single_param(1.0);
+        let gradients = get_single_param(0.5);
+
+        for _ in 0..steps {
+            optimizer.step(&mut network_params, &gradients, 0.1);
+        }
+
+        assert_eq!(network_params[0].weights, network_params[0].biases);
+
+        network_params[0].weights[(0, 0)]
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_sgd() {
+        assert_close(run_steps(OptimizerKind::Sgd, 2), 0.9);
+    }
+
+    #[test]
+    fn test_momentum() {
+        let momentum = OptimizerKind::Momentum {
+            momentum: 0.9,
+            nesterov: false,
+        };
+        // v_1 = 0.5, v_2 = 0.95
+        assert_close(run_steps(momentum, 2), 1.0 - 0.05 - 0.095);
+
+        let nesterov = OptimizerKind::Momentum {
+            momentum: 0.9,
+            nesterov: true,
+        };
+        // v_1 = 0.5 -> update 0.95, v_2 = 0.95 -> update 1.355
+        assert_close(run_steps(nesterov, 2), 1.0 - 0.095 - 0.1355);
+    }
+
+    #[test]
+    fn test_rms_prop() {
+        let rms_prop = OptimizerKind::RmsProp {
+            decay: 0.9,
+            epsilon: 1e-8,
+        };
+        // s = 0.1 * 0.25
+        assert_close(run_steps(rms_prop, 1), 1.0 - 0.05 / 0.025_f32.sqrt());
+    }
+
+    #[test]
+    fn test_adagrad() {
+        let adagrad = OptimizerKind::Adagrad { epsilon: 1e-8 };
+        // s_1 = 0.25, s_2 = 0.5
+        assert_close(run_steps(adagrad, 2), 1.0 - 0.1 - 0.05 / 0.5_f32.sqrt());
+    }
+
+    #[test]
+    fn test_adam() {
+        let adam = OptimizerKind::Adam {
+            beta_1: 0.9,
+            beta_2: 0.999,
+            epsilon: 1e-8,
+        };
+        // with a constant gradient the bias corrected step is alpha every time
+        assert_close(run_steps(adam, 3), 0.7);
+    }
+
+    #[test]
+    fn test_adam_past_i32_time_steps() {
+        let mut adam = OptimizerKind::Adam {
+            beta_1: 0.9,
+            beta_2: 0.999,
+            epsilon: 1e-8,
+        }
+        .build();
+        let time_step = i32::MAX as u64 + 10;
+
+        // converged moments of the constant gradient 0.5
+        adam.load_state(OptimizerState {
+            time_step,
+            buffers: vec![get_single_param(0.5), get_single_param(0.25)],
+        })
+        .unwrap();
+
+        let mut network_params = get_single_param(1.0);
+        adam.step(&mut network_params, &get_single_param(0.5), 0.1);
+
+        assert_close(network_params[0].weights[(0, 0)], 0.9);
+        assert_eq!(adam.state().time_step, time_step + 1);
+    }
+
+    #[test]
+    fn test_config() {
+        let adam = |beta_1, beta_2, epsilon| OptimizerKind::Adam {
+            beta_1,
+            beta_2,
+            epsilon,
+        };
+        let validate = |optimizer| {
+            TrainingConfig {
+                optimizer,
+                ..TrainingConfig::default()
+            }
+            .validate()
+        };
+
+        assert!(validate(adam(0.9, 0.999, 1e-8)).is_ok());
+        assert!(validate(OptimizerKind::Sgd).is_ok());
+
+        for optimizer in [
+            // the bias correction 1 - beta_1^t would divide by 0
+            adam(1.0, 0.999, 1e-8),
+            adam(0.9, 1.5, 1e-8),
+            adam(0.9, 0.999, 0.0),
+            adam(0.9, 0.999, f32::NAN),
+            OptimizerKind::Momentum {
+                momentum: -0.1,
+                nesterov: false,
+            },
+            OptimizerKind::RmsProp {
+                decay: 1.0,
+                epsilon: 1e-8,
+            },
+            OptimizerKind::Adagrad { epsilon: -1e-8 },
+        ] {
+            assert!(
+                matches!(validate(optimizer), Err(Error::InvalidConfig(_))),
+                "{optimizer:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::{default_activations, Activation},
+        config::{per_layer, TrainingConfig},
+        dense_matrix::Matrix,
+        matrix::create_rng,
+        network_functions::train,
+        regularization::{add_penalty_gradients, apply_constraints, penalty, Regularization},
+        types::{Layer, NetworkParams},
+    };
+
+    fn get_network_params() -> NetworkParams {
+        vec![Layer {
+            weights: Matrix::from(vec![vec![3.0, -4.0], vec![0.0, 0.5]]),
+            biases: Matrix::from(vec![vec![2.0], vec![-1.0]]),
+            activation: Activation::Relu,
+        }]
+    }
+
+    fn get_zero_gradients() -> NetworkParams {
+        let mut gradients = get_network_params();
+        gradients[0].weights = Matrix::zeroes(2, 2);
+        gradients[0].biases = Matrix::zeroes(2, 1);
+        gradients
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    fn weight_norm(network_params: &NetworkParams) -> f32 {
+        network_params
+            .iter()
+            .flat_map(|layer| layer.weights.as_slice())
+            .map(|w| w * w)
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    #[test]
+    fn test_penalty() {
+        let network_params = get_network_params();
+        let l1 = [Regularization {
+            l1: 0.1,
+            ..Regularization::default()
+        }];
+        let l2 = [Regularization {
+            l2: 0.2,
+            ..Regularization::default()
+        }];
+
+        // |3| + |-4| + |0| + |0.5| = 7.5, the biases are excluded by default
+        assert_close(penalty(&network_params, &l1, false), 0.75);
+        assert_close(penalty(&network_params, &l1, true), 1.05);
+        // 9 + 16 + 0 + 0.25 = 25.25
+        assert_close(penalty(&network_params, &l2, false), 2.525);
+        assert_eq!(
+            penalty(&network_params, &[Regularization::default()], true),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_penalty_gradients_match_numerical_gradients() {
+        let network_params = get_network_params();
+        let regularization = [Regularization {
+            l1: 0.3,
+            l2: 0.2,
+            ..Regularization::default()
+        }];
+        let mut gradients = get_zero_gradients();
+        add_penalty_gradients(&network_params, &mut gradients, &regularization, true);
+
+        let epsilon = 1e-2;
+        for i in 0..4 {
+            // w = 0 has no derivative, the subgradient 0 is used
+            if network_params[0].weights.as_slice()[i] == 0.0 {
+                assert_eq!(gradients[0].weights.as_slice()[i], 0.0);
+                continue;
+            }
+
+            let mut plus = network_params.clone();
+            plus[0].weights.as_mut_slice()[i] += epsilon;
+            let mut minus = network_params.clone();
+            minus[0].weights.as_mut_slice()[i] -= epsilon;
+
+            let numerical = (penalty(&plus, &regularization, true)
+                - penalty(&minus, &regularization, true))
+                / (2.0 * epsilon);
+            assert_close(gradients[0].weights.as_slice()[i], numerical);
+        }
+        assert_close(gradients[0].biases.as_slice()[0], 0.3 + 0.2 * 2.0);
+
+        let mut weights_only = get_zero_gradients();
+        add_penalty_gradients(&network_params, &mut weights_only, &regularization, false);
+        assert_eq!(weights_only[0].biases, Matrix::zeroes(2, 1));
+    }
+
+    #[test]
+    fn test_decoupled_weight_decay() {
+        let mut network_params = get_network_params();
+        let regularization = [Regularization {
+            weight_decay: 0.5,
+            ..Regularization::default()
+        }];
+
+        apply_constraints(&mut network_params, &regularization, false, 0.2);
+
+        for (actual, expected) in network_params[0]
+            .weights
+            .as_slice()
+            .iter()
+            .zip([2.7, -3.6, 0.0, 0.45])
+        {
+            assert_close(*actual, expected);
+        }
+        assert_eq!(network_params[0].biases, get_network_params()[0].biases);
+        // not part of the loss
+        assert_eq!(penalty(&network_params, &regularization, false), 0.0);
+    }
+
+    #[test]
+    fn test_max_norm() {
+        let mut network_params = get_network_params();
+        let regularization = [Regularization {
+            max_norm: Some(1.0),
+            ..Regularization::default()
+        }];
+
+        apply_constraints(&mut network_params, &regularization, true, 0.1);
+
+        // the row of norm 5 is scaled down, the row of norm 0.5 and the biases are kept
+        assert_eq!(
+            network_params[0].weights,
+            Matrix::from(vec![vec![0.6, -0.8], vec![0.0, 0.5]])
+        );
+        assert_eq!(network_params[0].biases, get_network_params()[0].biases);
+    }
+
+    #[test]
+    fn test_training_with_regularization() {
+        let labels = Matrix::from_fn(1, 20, |_, j| (j % 2) as f32);
+        let data = Matrix::from_fn(2, 20, |i, j| ((i + j) % 2) as f32);
+        let config = TrainingConfig {
+            layer_sizes: vec![2, 6, 2],
+            activations: default_activations(2),
+            iterations: 30,
+            alpha: 0.5,
+            ..TrainingConfig::default()
+        };
+        let train_with = |regularization: Regularization| {
+            let config = TrainingConfig {
+                regularization: vec![regularization; 2],
+                ..config.clone()
+            };
+            train(
+                labels.clone(),
+                data.clone(),
+                &config,
+                &mut create_rng(Some(1)),
+            )
+        };
+
+        let plain = weight_norm(&train_with(Regularization::default()));
+        let l2 = weight_norm(&train_with(Regularization {
+            l2: 0.1,
+            ..Regularization::default()
+        }));
+        let decayed = weight_norm(&train_with(Regularization {
+            weight_decay: 0.1,
+            ..Regularization::default()
+        }));
+        assert!(l2 < plain, "{l2} >= {plain}");
+        assert!(decayed < plain, "{decayed} >= {plain}");
+
+        let clipped = train_with(Regularization {
+            max_norm: Some(0.5),
+            ..Regularization::default()
+        });
+        for layer in clipped {
+            for unit in layer.weights.as_slice().chunks(layer.weights.cols()) {
+                assert!(unit.iter().map(|w| w * w).sum::<f32>().sqrt() <= 0.5 + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_config() {
+        assert_eq!(per_layer("L2", &[0.1], 3).unwrap(), vec![0.1; 3]);
+        assert_eq!(per_layer("L2", &[0.1, 0.2], 2).unwrap(), vec![0.1, 0.2]);
+        assert!(per_layer("L2", &[0.1, 0.2], 3).is_err());
+
+        let negative = TrainingConfig {
+            regularization: vec![
+                Regularization {
+                    l1: -0.1,
+                    ..Regularization::default()
+                };
+                2
+            ],
+            ..TrainingConfig::default()
+        };
+        assert!(negative.validate().is_err());
+
+        let missing = TrainingConfig {
+            regularization: vec![Regularization::default()],
+            ..TrainingConfig::default()
+        };
+        assert!(missing.validate().is_err());
+    }
+}
@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::default_activations,
+        config::{schedule_from_name, TrainingConfig},
+        dense_matrix::Matrix,
+        matrix::create_rng,
+        network_functions::{compute_metrics, continue_training, TrainingState},
+        schedule::{PlateauState, Schedule},
+    };
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    fn alphas(schedule: Schedule, warmup: usize, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|i| schedule.alpha(1.0, warmup, i, &PlateauState::default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_warmup() {
+        let expected = [0.25, 0.5, 0.75, 1.0, 1.0, 1.0];
+
+        for (actual, expected) in alphas(Schedule::Constant, 4, 6).into_iter().zip(expected) {
+            assert_close(actual, expected);
+        }
+
+        // the decay starts counting after the warmup
+        let step = Schedule::Step {
+            step_size: 2,
+            gamma: 0.5,
+        };
+        assert_eq!(alphas(step, 2, 6), vec![0.5, 1.0, 1.0, 1.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_decay() {
+        let step = Schedule::Step {
+            step_size: 3,
+            gamma: 0.1,
+        };
+        let expected = [1.0, 1.0, 1.0, 0.1, 0.1, 0.1, 0.01];
+        for (actual, expected) in alphas(step, 0, 7).into_iter().zip(expected) {
+            assert_close(actual, expected);
+        }
+
+        let exponential = Schedule::Exponential { gamma: 0.5 };
+        assert_eq!(alphas(exponential, 0, 4), vec![1.0, 0.5, 0.25, 0.125]);
+    }
+
+    #[test]
+    fn test_cosine_restarts() {
+        let cosine = Schedule::Cosine {
+            period: 4,
+            period_mult: 2,
+            min_alpha: 0.2,
+        };
+        let alphas = alphas(cosine, 0, 13);
+
+        // 4 iterations, then 8 iterations, then a restart again
+        for (i, expected) in [(0, 1.0), (2, 0.6), (4, 1.0), (8, 0.6), (12, 1.0)] {
+            assert_close(alphas[i], expected);
+        }
+        assert!(alphas[..4].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(alphas.iter().all(|alpha| *alpha > 0.2 && *alpha <= 1.0));
+    }
+
+    #[test]
+    fn test_reduce_on_plateau() {
+        let plateau = Schedule::Plateau {
+            factor: 0.5,
+            patience: 2,
+            min_alpha: 0.2,
+        };
+        let mut state = PlateauState::default();
+        let mut alphas = Vec::new();
+
+        for loss in [
+            1.0, 0.8, 0.8, 0.9, 0.8, 0.7, 0.7, 0.7, 0.7, 0.7, 0.7, 0.7, 0.7,
+        ] {
+            state.update(&plateau, loss);
+            alphas.push(plateau.alpha(1.0, 0, alphas.len(), &state));
+        }
+
+        assert_eq!(
+            alphas,
+            vec![1.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.5, 0.25, 0.25, 0.2, 0.2, 0.2, 0.2]
+        );
+        assert_eq!(state.best_loss, 0.7);
+
+        // a patience of 0 behaves like 1
+        let impatient = Schedule::Plateau {
+            factor: 0.5,
+            patience: 0,
+            min_alpha: 0.0,
+        };
+        let mut state = PlateauState::default();
+        let scales: Vec<f32> = [1.0, 1.0, 1.0]
+            .into_iter()
+            .map(|loss| {
+                state.update(&impatient, loss);
+                state.scale
+            })
+            .collect();
+        assert_eq!(scales, vec![1.0, 0.5, 0.25]);
+
+        // only the plateau schedule keeps track of the loss
+        let mut constant_state = PlateauState::default();
+        constant_state.update(&Schedule::Constant, 1.0);
+        assert_eq!(constant_state, PlateauState::default());
+    }
+
+    #[test]
+    fn test_config() {
+        assert_eq!(schedule_from_name("Constant").unwrap(), Schedule::Constant);
+        assert!(matches!(
+            schedule_from_name("cosine").unwrap(),
+            Schedule::Cosine { .. }
+        ));
+        assert!(schedule_from_name("cyclic").is_err());
+
+        for schedule in [
+            Schedule::Step {
+                step_size: 0,
+                gamma: 0.1,
+            },
+            Schedule::Cosine {
+                period: 10,
+                period_mult: 0,
+                min_alpha: 0.0,
+            },
+            Schedule::Plateau {
+                factor: 1.5,
+                patience: 2,
+                min_alpha: 0.0,
+            },
+            Schedule::Step {
+                step_size: 10,
+                gamma: 0.0,
+            },
+            Schedule::Exponential { gamma: 1.5 },
+            // above the default alpha of 0.15
+            Schedule::Cosine {
+                period: 10,
+                period_mult: 1,
+                min_alpha: 0.5,
+            },
+            Schedule::Plateau {
+                factor: 0.5,
+                patience: 2,
+                min_alpha: -0.1,
+            },
+        ] {
+            let config = TrainingConfig {
+                schedule,
+                ..TrainingConfig::default()
+            };
+            assert!(config.validate().is_err(), "{schedule:?}");
+        }
+
+        let cosine = TrainingConfig {
+            schedule: Schedule::Cosine {
+                period: 10,
+                period_mult: 1,
+                min_alpha: 0.01,
+            },
+            ..TrainingConfig::default()
+        };
+        assert!(cosine.validate().is_ok());
+
+        for alpha in [-1.0, 0.0, f32::NAN, f32::INFINITY] {
+            let config = TrainingConfig {
+                alpha,
+                ..TrainingConfig::default()
+            };
+            assert!(config.validate().is_err(), "{alpha}");
+        }
+    }
+
+    #[test]
+    fn test_plateau_follows_validation_loss() {
+        let labels = Matrix::from_fn(1, 20, |_, j| (j % 2) as f32);
+        let data = Matrix::from_fn(2, 20, |i, j| ((i + j) % 2) as f32);
+        let validation_labels = Matrix::from_fn(1, 6, |_, j| (j % 2) as f32);
+        let validation_data = Matrix::from_fn(2, 6, |i, j| ((i + j) % 2) as f32);
+
+        let config = TrainingConfig {
+            layer_sizes: vec![2, 4, 2],
+            activations: default_activations(2),
+            iterations: 8,
+            alpha: 0.5,
+            schedule: Schedule::Plateau {
+                factor: 0.5,
+                patience: 2,
+                min_alpha: 0.0,
+            },
+            ..TrainingConfig::default()
+        };
+
+        let mut state = TrainingState::new(&config, &mut create_rng(Some(0)));
+        continue_training(
+            &mut state,
+            &labels,
+            &data,
+            Some((&validation_labels, &validation_data)),
+            &config,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        let (final_loss, _) = compute_metrics(
+            &state.network_params,
+            &validation_labels,
+            &validation_data,
+            config.loss,
+        );
+        assert!(state.plateau.best_loss <= final_loss);
+        assert!(state.plateau.best_loss < f32::INFINITY);
+    }
+}
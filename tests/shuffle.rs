@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rustnet::common::{
+        dense_matrix::Matrix,
+        matrix::{create_rng, fisher_yates, shuffle, shuffle_matrix, shuffled_indices},
+        network_functions::{prepare_data, shuffle_samples},
+    };
+
+    const TRIALS: usize = 60_000;
+    // chi-square with 5 degrees of freedom exceeds this with probability 0.001
+    const CHI_SQUARE_5_DOF: f64 = 20.52;
+
+    /// Counts how often each of the 6 orders of [0, 1, 2] comes out of `shuffle_fn`.
+    fn permutation_counts<F: FnMut(&mut [usize])>(mut shuffle_fn: F) -> [usize; 6] {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut counts = [0; 6];
+
+        for _ in 0..TRIALS {
+            let mut items = [0, 1, 2];
+            shuffle_fn(&mut items);
+            counts[permutations.iter().position(|p| *p == items).unwrap()] += 1;
+        }
+
+        counts
+    }
+
+    fn chi_square(counts: &[usize]) -> f64 {
+        let expected = TRIALS as f64 / counts.len() as f64;
+
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_every_permutation_is_equally_likely() {
+        let mut rng = create_rng(Some(11));
+        let counts = permutation_counts(|items| shuffle(items, &mut rng));
+
+        assert!(chi_square(&counts) < CHI_SQUARE_5_DOF, "{counts:?}");
+    }
+
+    #[test]
+    fn test_naive_shuffle_is_detected() {
+        // swapping every index with any index gives 27 equally likely paths over 6 orders
+        let mut rng = create_rng(Some(11));
+        let counts = permutation_counts(|items| {
+            for i in 0..items.len() {
+                let j = rng.gen_range(0..items.len());
+                items.swap(i, j);
+            }
+        });
+
+        assert!(chi_square(&counts) > CHI_SQUARE_5_DOF, "{counts:?}");
+    }
+
+    #[test]
+    fn test_every_position_is_equally_likely() {
+        let mut rng = create_rng(Some(5));
+        let mut counts = vec![vec![0; 8]; 8];
+
+        for _ in 0..TRIALS {
+            for (position, &item) in shuffled_indices(8, &mut rng).iter().enumerate() {
+                counts[item][position] += 1;
+            }
+        }
+
+        for item_counts in counts {
+            // 7 degrees of freedom, p = 0.001
+            assert!(chi_square(&item_counts) < 24.32, "{item_counts:?}");
+        }
+    }
+
+    #[test]
+    fn test_shuffles_in_lockstep() {
+        let mut labels: Vec<usize> = (0..100).collect();
+        let mut matrix: Vec<Vec<usize>> = (0..100).map(|i| vec![i, i * 2]).collect();
+
+        fisher_yates(100, &mut create_rng(Some(2)), |i, j| {
+            labels.swap(i, j);
+            matrix.swap(i, j);
+        });
+
+        assert_ne!(labels, (0..100).collect::<Vec<_>>());
+        for (label, row) in labels.iter().zip(&matrix) {
+            assert_eq!(row, &vec![*label, label * 2]);
+        }
+
+        let mut rows = matrix.clone();
+        shuffle_matrix(&mut rows, &mut create_rng(Some(3)));
+        rows.sort();
+        matrix.sort();
+        assert_eq!(rows, matrix);
+    }
+
+    #[test]
+    fn test_samples_keep_their_labels() {
+        let labels = Matrix::from_fn(1, 50, |_, j| j as f32);
+        let data = Matrix::from_fn(3, 50, |i, j| (i * 100 + j) as f32);
+
+        let (shuffled_labels, shuffled_data) =
+            shuffle_samples(&labels, &data, &mut create_rng(Some(4)));
+
+        assert_ne!(shuffled_labels, labels);
+        for (j, &label) in shuffled_labels.row(0).iter().enumerate() {
+            for i in 0..3 {
+                assert_eq!(shuffled_data[(i, j)], i as f32 * 100.0 + label);
+            }
+        }
+
+        let dev_set: Vec<Vec<f32>> = (0..50)
+            .map(|j| vec![j as f32, j as f32 * 2.0, 255.0])
+            .collect();
+        let (labels, data) = prepare_data(dev_set, &mut create_rng(Some(4)));
+
+        assert_eq!(labels, shuffled_labels);
+        for (j, &label) in labels.row(0).iter().enumerate() {
+            assert_eq!(data.column(j), vec![label * 2.0 / 255.0, 1.0]);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::simd;
+
+    // lengths around the 4 and 8 lane register widths so both the vector body and the scalar tail run
+    const LENGTHS: [usize; 8] = [0, 1, 3, 4, 7, 8, 17, 33];
+
+    type BinaryKernel = (fn(&mut [f32], &[f32]), fn(f32, f32) -> f32);
+    type ScalarKernel = (fn(&mut [f32], f32), fn(f32, f32) -> f32);
+
+    fn get_values(len: usize, seed: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| match (i + seed) % 7 {
+                0 => 0.0,
+                1 => -0.0,
+                2 => f32::NAN,
+                n => (n as f32 - 4.5) * (i + 1) as f32 / 3.0,
+            })
+            .collect()
+    }
+
+    fn assert_bitwise_eq(result: &[f32], expected: &[f32]) {
+        let result_bits: Vec<u32> = result.iter().map(|x| x.to_bits()).collect();
+        let expected_bits: Vec<u32> = expected.iter().map(|x| x.to_bits()).collect();
+
+        assert_eq!(result_bits, expected_bits, "{result:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_binary_kernels() {
+        let kernels: [BinaryKernel; 5] = [
+            (simd::add, |x, y| x + y),
+            (simd::subtract, |x, y| x - y),
+            (simd::multiply, |x, y| x * y),
+            (simd::divide, |x, y| x / y),
+            (simd::max, |x, y| if y > x { y } else { x }),
+        ];
+
+        for (kernel, scalar) in kernels {
+            for len in LENGTHS {
+                let (target, other) = (get_values(len, 0), get_values(len, 3));
+                let expected: Vec<f32> = target
+                    .iter()
+                    .zip(&other)
+                    .map(|(&x, &y)| scalar(x, y))
+                    .collect();
+
+                let mut result = target.clone();
+                kernel(&mut result, &other);
+
+                assert_bitwise_eq(&result, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_kernels() {
+        let kernels: [ScalarKernel; 5] = [
+            (simd::add_scalar, |x, y| x + y),
+            (simd::subtract_scalar, |x, y| x - y),
+            (simd::scale, |x, y| x * y),
+            (simd::divide_scalar, |x, y| x / y),
+            (simd::max_scalar, |x, y| if x > y { x } else { y }),
+        ];
+
+        for (kernel, scalar) in kernels {
+            for len in LENGTHS {
+                for value in [0.15, -2.0, 0.0] {
+                    let target = get_values(len, 1);
+                    let expected: Vec<f32> = target.iter().map(|&x| scalar(x, value)).collect();
+
+                    let mut result = target.clone();
+                    kernel(&mut result, value);
+
+                    assert_bitwise_eq(&result, &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_relu() {
+        let mut result = get_values(17, 0);
+        let expected: Vec<f32> = result
+            .iter()
+            .map(|&x| if x > 0.0 { x } else { 0.0 })
+            .collect();
+
+        simd::relu(&mut result);
+
+        assert_bitwise_eq(&result, &expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice lengths must match: 3 and 2!")]
+    fn test_length_mismatch() {
+        simd::add(&mut [1.0, 2.0, 3.0], &[1.0, 2.0]);
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use rustnet::common::{
+        activation::default_activations,
+        config::TrainingConfig,
+        dense_matrix::Matrix,
+        matrix::create_rng,
+        network_functions::{forward_propagation, get_accuracy, get_predictions, train},
+    };
+
+    // two linearly separable classes, samples are stored column wise like prepare_data does
+    fn get_separable_data() -> (Matrix, Matrix) {
+        let labels: Vec<f32> = (0..40).map(|i| (i % 2) as f32).collect();
+        let data = vec![
+            labels.iter().map(|label| 1.0 - label).collect(),
+            labels.clone(),
+        ];
+
+        (Matrix::from(vec![labels]), Matrix::from(data))
+    }
+
+    #[test]
+    fn test_mini_batch_training() {
+        let (labels, data) = get_separable_data();
+        let config = TrainingConfig {
+            layer_sizes: vec![2, 8, 2],
+            activations: default_activations(2),
+            iterations: 50,
+            alpha: 0.5,
+            batch_size: Some(8),
+            ..TrainingConfig::default()
+        };
+
+        let network_params = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(0)),
+        );
+
+        let forward_cache = forward_propagation(&network_params, &data);
+        let prediction = get_predictions(&forward_cache.last().unwrap().activation);
+
+        assert_eq!(get_accuracy(&labels, prediction), 100.0);
+    }
+
+    #[test]
+    fn test_seeded_training_is_reproducible() {
+        let (labels, data) = get_separable_data();
+        let config = TrainingConfig {
+            layer_sizes: vec![2, 8, 2],
+            activations: default_activations(2),
+            iterations: 5,
+            batch_size: Some(8),
+            ..TrainingConfig::default()
+        };
+
+        let first = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(7)),
+        );
+        let second = train(
+            labels.clone(),
+            data.clone(),
+            &config,
+            &mut create_rng(Some(7)),
+        );
+        let other_seed = train(labels, data, &config, &mut create_rng(Some(8)));
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_seed);
+    }
+}